@@ -0,0 +1,35 @@
+/*
+ * C declarations for the `ffi` feature of the xous-semver crate. Build a static
+ * library to link against with:
+ *
+ *   cargo rustc --release --no-default-features --features ffi --crate-type staticlib
+ */
+#ifndef XOUS_SEMVER_H
+#define XOUS_SEMVER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define XOUS_SEMVER_OK          0
+#define XOUS_SEMVER_ERR_NULL   -1
+#define XOUS_SEMVER_ERR_PARSE  -2
+#define XOUS_SEMVER_ERR_BUFFER -3
+
+/* Field order and widths match the 16-byte serialization. */
+typedef struct {
+    uint16_t maj;
+    uint16_t min;
+    uint16_t rev;
+    uint16_t extra;
+    uint32_t commit;
+    uint32_t has_commit;
+} XousSemVer;
+
+int32_t xous_semver_parse(const char *s, XousSemVer *out);
+/* Returns the string length (excluding the NUL) or a negative error. */
+int32_t xous_semver_format(const XousSemVer *ver, char *buf, size_t len);
+/* Returns -1, 0 or 1; the commit is not considered. */
+int32_t xous_semver_cmp(const XousSemVer *a, const XousSemVer *b);
+int32_t xous_semver_to_bytes(const XousSemVer *ver, uint8_t out[16]);
+
+#endif /* XOUS_SEMVER_H */
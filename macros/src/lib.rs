@@ -0,0 +1,125 @@
+//! Procedural macros producing `xous_semver::SemVer` constants. The expansions name
+//! `::xous_semver`, so crates using these macros must depend on `xous-semver` as well.
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use proc_macro::{TokenStream, TokenTree};
+use xous_semver::{GitQuery, SemVer};
+
+/// Parses a version string at compile time, reporting errors as compile errors.
+///
+/// ```
+/// use xous_semver::SemVer;
+/// const VERSION: SemVer = xous_semver_macros::semver!("v0.9.8-760-gabcd1234");
+/// assert_eq!(VERSION, SemVer::from_str("v0.9.8-760-gabcd1234").unwrap());
+/// ```
+///
+/// ```compile_fail
+/// // error: invalid version "0.9.8": semver does not start with 'v'!
+/// const VERSION: xous_semver::SemVer = xous_semver_macros::semver!("0.9.8");
+/// ```
+#[proc_macro]
+pub fn semver(input: TokenStream) -> TokenStream {
+    let parsed = string_literal(input).and_then(|s| {
+        SemVer::from_str(&s).map_err(|e| format!("invalid version {:?}: {}", s, e))
+    });
+    match parsed {
+        Ok(version) => expand(&version),
+        Err(msg) => compile_error(&msg),
+    }
+}
+
+/// Runs `git describe` on the invoking crate's repository at compile time and expands to
+/// the resulting `SemVer`. The expansion also `include_bytes!`s `.git/HEAD` and the ref it
+/// points at, so that cargo rebuilds the crate when HEAD moves.
+///
+/// `git_semver!(fallback = "v0.0.0")` uses the given version instead of failing the build
+/// when git can't describe the repository, e.g. when building from a source tarball.
+///
+/// ```
+/// const VERSION: xous_semver::SemVer = xous_semver_macros::git_semver!(fallback = "v0.0.0");
+/// ```
+#[proc_macro]
+pub fn git_semver(input: TokenStream) -> TokenStream {
+    let dir = PathBuf::from(std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string()));
+    let mut query = GitQuery::new().dir(&dir);
+    match fallback(input) {
+        Ok(Some(version)) => query = query.fallback_to(version),
+        Ok(None) => {}
+        Err(msg) => return compile_error(&msg),
+    }
+    let version = match query.describe() {
+        Ok(info) => info.version,
+        Err(e) => return compile_error(&format!("git_semver!: {}", e)),
+    };
+    let mut tracked = String::new();
+    for path in tracked_files(&dir) {
+        tracked.push_str(&format!("const _: &[u8] = ::core::include_bytes!({:?});", path.display().to_string()));
+    }
+    format!("{{ {} {} }}", tracked, expand(&version)).parse().unwrap()
+}
+
+fn fallback(input: TokenStream) -> Result<Option<SemVer>, String> {
+    let tokens: Vec<TokenTree> = input.into_iter().collect();
+    match tokens.as_slice() {
+        [] => Ok(None),
+        [TokenTree::Ident(key), TokenTree::Punct(eq), value] if key.to_string() == "fallback" && eq.as_char() == '=' => {
+            let s = string_literal(value.clone().into())?;
+            SemVer::from_str(&s).map(Some).map_err(|e| format!("invalid fallback version {:?}: {}", s, e))
+        }
+        _ => Err("expected no arguments or `fallback = \"v...\"`".to_string()),
+    }
+}
+
+// the files whose changes should trigger a rebuild: HEAD, the branch it points at, and
+// packed-refs, which is where refs go after `git gc`. Loose tags aren't tracked.
+fn tracked_files(dir: &Path) -> Vec<PathBuf> {
+    let git = |args: &[&str]| Command::new("git")
+        .arg("-C").arg(dir)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+    let (Some(git_dir), Some(common_dir)) = (
+        git(&["rev-parse", "--absolute-git-dir"]),
+        git(&["rev-parse", "--path-format=absolute", "--git-common-dir"]),
+    ) else {
+        return Vec::new();
+    };
+    let mut files = vec![Path::new(&git_dir).join("HEAD"), Path::new(&common_dir).join("packed-refs")];
+    if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"]) {
+        files.push(Path::new(&common_dir).join(head_ref));
+    }
+    files.retain(|f| f.is_file());
+    files
+}
+
+// accepts exactly one plain string literal; raw strings and escapes have no business
+// in a version
+fn string_literal(input: TokenStream) -> Result<String, String> {
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        _ => return Err("expected a single string literal, e.g. semver!(\"v0.9.8\")".to_string()),
+    };
+    literal.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .filter(|s| !s.contains('\\'))
+        .map(String::from)
+        .ok_or_else(|| format!("expected a plain string literal, found {}", literal))
+}
+
+pub(crate) fn expand(version: &SemVer) -> TokenStream {
+    let commit = match version.commit {
+        Some(commit) => format!("::core::option::Option::Some(0x{:08x}u32)", commit),
+        None => "::core::option::Option::None".to_string(),
+    };
+    format!(
+        "::xous_semver::SemVer {{ maj: {}u16, min: {}u16, rev: {}u16, extra: {}u16, commit: {} }}",
+        version.maj, version.min, version.rev, version.extra, commit
+    ).parse().unwrap()
+}
+
+pub(crate) fn compile_error(msg: &str) -> TokenStream {
+    format!("::core::compile_error!({:?})", msg).parse().unwrap()
+}
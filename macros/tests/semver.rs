@@ -0,0 +1,21 @@
+use xous_semver::SemVer;
+use xous_semver_macros::semver;
+
+const DEV: SemVer = semver!("v0.9.8-760-gabcd1234");
+const RELEASE: SemVer = semver!("v1.2.3");
+
+#[test]
+fn test_semver_macro() {
+    assert_eq!(DEV, SemVer::from_str("v0.9.8-760-gabcd1234").unwrap());
+    assert_eq!(RELEASE, SemVer::new(1, 2, 3));
+    assert_eq!(semver!("v0.9.8-760"), SemVer::from_str("v0.9.8-760").unwrap());
+    assert!(DEV < RELEASE);
+}
+
+#[test]
+fn test_git_semver_macro() {
+    const FALLBACK: SemVer = semver!("v0.0.0");
+    const BUILD: SemVer = xous_semver_macros::git_semver!(fallback = "v0.0.0");
+    let dir = env!("CARGO_MANIFEST_DIR");
+    assert_eq!(BUILD, SemVer::from_git_in(dir).unwrap_or(FALLBACK));
+}
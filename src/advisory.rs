@@ -0,0 +1,78 @@
+//! Security advisories, for warning users who run firmware with known issues.
+//!
+//! The binary form of an advisory is the 36-byte `affected` range, the 16-byte
+//! `fixed_in` serialization, then the little-endian `id`, 56 bytes in total. Feeds are
+//! a sequence of these; authenticating the feed is up to the caller.
+use crate::{Error, SemVer, VersionRange};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Advisory {
+    /// The versions with the issue.
+    pub affected: VersionRange,
+    /// The first version with the fix, to suggest as an upgrade.
+    pub fixed_in: SemVer,
+    pub id: u32,
+}
+
+impl Advisory {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = VersionRange::SERIALIZED_LEN + SemVer::SERIALIZED_LEN + 4;
+
+    /// True if `ver` has the issue: it is in the affected range and older than the fix.
+    pub fn affects(&self, ver: &SemVer) -> bool {
+        self.affected.contains(ver) && *ver < self.fixed_in
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..Advisory::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: Advisory::SERIALIZED_LEN })?;
+        self.affected.write_to(dest)?;
+        dest[36..52].copy_from_slice(&<[u8; 16]>::from(&self.fixed_in));
+        dest[52..56].copy_from_slice(&self.id.to_le_bytes());
+        Ok(Advisory::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<Advisory, Error> {
+        let (affected, rest) = buf.split_first_chunk::<{ VersionRange::SERIALIZED_LEN }>().ok_or(Error::Truncated)?;
+        let (fixed_in, rest) = rest.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (id, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        Ok(Advisory {
+            affected: VersionRange::read_from(affected)?,
+            fixed_in: SemVer::from_bytes_strict(fixed_in)?,
+            id: u32::from_le_bytes(*id),
+        })
+    }
+}
+
+impl SemVer {
+    /// The advisories that affect this version, in feed order.
+    pub fn vulnerabilities<'a>(&'a self, advisories: &'a [Advisory]) -> impl Iterator<Item = &'a Advisory> + 'a {
+        advisories.iter().filter(move |advisory| advisory.affects(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_advisories() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let feed = [
+            Advisory { affected: VersionRange::half_open(v("v0.9.0"), v("v0.10.0")), fixed_in: v("v0.9.8-40"), id: 1 },
+            Advisory { affected: VersionRange::inclusive(v("v0.9.5"), v("v0.9.9")), fixed_in: v("v0.9.10"), id: 2 },
+        ];
+        let ids = |s| v(s).vulnerabilities(&feed).map(|a| a.id).collect::<Vec<_>>();
+        assert_eq!(ids("v0.9.4"), [1]);
+        assert_eq!(ids("v0.9.8-39"), [1, 2]);
+        assert_eq!(ids("v0.9.8-40"), [2]);
+        assert_eq!(ids("v0.9.10"), [0u32; 0]);
+        assert_eq!(ids("v0.8.0"), [0u32; 0]);
+
+        let mut buf = [0u8; 56];
+        assert_eq!(feed[1].write_to(&mut buf), Ok(56));
+        assert_eq!(buf[52..], [2, 0, 0, 0]);
+        assert_eq!(Advisory::read_from(&buf), Ok(feed[1]));
+        assert_eq!(Advisory::read_from(&buf[..55]), Err(Error::Truncated));
+        assert_eq!(feed[1].write_to(&mut buf[..55]), Err(Error::BufferTooSmall { needed: 56 }));
+    }
+}
@@ -0,0 +1,65 @@
+//! Build-time version floors.
+use crate::SemVer;
+
+/// Fails the build if a version is below a required floor, so that gateware/loader
+/// incompatibilities are caught at build time rather than at boot. The version may be
+/// a `SemVer` constant, a string literal, or `env!("NAME")` (e.g. as set by
+/// `build::emit_cargo_env`); the floor is a string literal.
+///
+/// ```
+/// use xous_semver::{assert_semver_ge, SemVer};
+/// const LOADER: SemVer = SemVer::new(0, 9, 12);
+/// assert_semver_ge!(LOADER, "v0.9.10");
+/// assert_semver_ge!("v0.9.10-3-gabcd1234", "v0.9.10");
+/// ```
+///
+/// With a `build.rs` calling `build::emit_cargo_env("XOUS_SEMVER")`, the captured build
+/// version is checked with `assert_semver_ge!(env!("XOUS_SEMVER"), "v0.9.10");`.
+///
+/// ```compile_fail
+/// xous_semver::assert_semver_ge!(xous_semver::SemVer::new(0, 9, 9), "v0.9.10");
+/// ```
+///
+/// Like `SemVer`'s `Ord`, the commit is not considered.
+#[macro_export]
+macro_rules! assert_semver_ge {
+    (env!($name:literal), $floor:literal) => {
+        const _: () = $crate::__assert_semver_ge(
+            $crate::__parse_or_panic(::core::env!($name)),
+            $crate::__parse_or_panic($floor),
+        );
+    };
+    ($version:literal, $floor:literal) => {
+        const _: () = $crate::__assert_semver_ge(
+            $crate::__parse_or_panic($version),
+            $crate::__parse_or_panic($floor),
+        );
+    };
+    ($version:expr, $floor:literal) => {
+        const _: () = $crate::__assert_semver_ge($version, $crate::__parse_or_panic($floor));
+    };
+}
+
+#[doc(hidden)]
+pub const fn __parse_or_panic(s: &str) -> SemVer {
+    match SemVer::from_str_const(s) {
+        Ok(version) => version,
+        Err(_) => panic!("assert_semver_ge!: not a valid version"),
+    }
+}
+
+#[doc(hidden)]
+pub const fn __assert_semver_ge(version: SemVer, floor: SemVer) {
+    let v = [version.maj, version.min, version.rev, version.extra];
+    let f = [floor.maj, floor.min, floor.rev, floor.extra];
+    let mut i = 0;
+    while i < v.len() {
+        if v[i] != f[i] {
+            if v[i] < f[i] {
+                panic!("assert_semver_ge!: version is below the required minimum");
+            }
+            return;
+        }
+        i += 1;
+    }
+}
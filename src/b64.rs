@@ -0,0 +1,65 @@
+//! Base64 (standard alphabet, padded) helpers for carrying the binary encodings in
+//! HTTP update manifests. Everything works on caller-provided buffers, so no allocator
+//! is needed.
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use crate::{Error, SemVer};
+
+/// Length of the base64 form of the 16-byte serialization.
+pub const SEMVER_B64_LEN: usize = 24;
+
+/// Encodes arbitrary bytes (e.g. a TLV record) into `out`, returning the number of
+/// ASCII characters written.
+pub fn encode(bytes: &[u8], out: &mut [u8]) -> Result<usize, Error> {
+    let needed = base64::encoded_len(bytes.len(), true).ok_or(Error::Malformed("input too long"))?;
+    STANDARD.encode_slice(bytes, out).map_err(|_| Error::BufferTooSmall { needed })
+}
+
+/// Decodes base64 text into `out`, returning the number of bytes written.
+pub fn decode(text: &str, out: &mut [u8]) -> Result<usize, Error> {
+    STANDARD.decode_slice(text.trim(), out).map_err(|e| match e {
+        base64::DecodeSliceError::OutputSliceTooSmall =>
+            Error::BufferTooSmall { needed: base64::decoded_len_estimate(text.trim().len()) },
+        base64::DecodeSliceError::DecodeError(_) => Error::Malformed("invalid base64"),
+    })
+}
+
+impl SemVer {
+    /// The 16-byte serialization as padded base64 ASCII.
+    pub fn to_base64(&self) -> [u8; SEMVER_B64_LEN] {
+        let mut out = [0u8; SEMVER_B64_LEN];
+        encode(&<[u8; 16]>::from(self), &mut out).unwrap();
+        out
+    }
+    pub fn from_base64(text: &str) -> Result<SemVer, Error> {
+        // the decoder wants room for its length estimate, which overshoots by up to 2
+        let mut bytes = [0u8; 18];
+        match decode(text, &mut bytes)? {
+            16 => Ok(SemVer::from(<[u8; 16]>::try_from(&bytes[..16]).unwrap())),
+            _ => Err(Error::Malformed("base64 payload is not 16 bytes")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_base64() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(&ver.to_base64(), b"AAAJAAgA+AI0Es2rAQAAAA==");
+        assert_eq!(SemVer::from_base64("AAAJAAgA+AI0Es2rAQAAAA==").unwrap(), ver);
+        assert_eq!(SemVer::from_base64("AAAJAAgA+AI0Es2rAQAA"), Err(Error::Malformed("base64 payload is not 16 bytes")));
+        assert_eq!(SemVer::from_base64("AAAJAAgA+AI0Es2r*QAAAA=="), Err(Error::Malformed("invalid base64")));
+
+        // extended encodings go through the slice helpers
+        let mut tlv = [0u8; 32];
+        let len = ver.encode_tlv(&mut tlv).unwrap();
+        let mut text = [0u8; 32];
+        assert_eq!(encode(&tlv[..len], &mut text[..8]), Err(Error::BufferTooSmall { needed: 32 }));
+        let text_len = encode(&tlv[..len], &mut text).unwrap();
+        let mut back = [0u8; 32];
+        let back_len = decode(core::str::from_utf8(&text[..text_len]).unwrap(), &mut back).unwrap();
+        assert_eq!(SemVer::decode_tlv(&back[..back_len]).unwrap(), ver);
+    }
+}
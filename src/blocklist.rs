@@ -0,0 +1,172 @@
+//! Versions pulled for bricking bugs, which the updater must refuse to install.
+//!
+//! The binary form is a little-endian entry count, then each entry: a little-endian kind
+//! word, then for an exact version its 16-byte serialization, or for a range its 36-byte
+//! `VersionRange` form.
+use crate::{Error, SemVer, VersionRange};
+
+const EXACT: u32 = 0;
+const RANGE: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockEntry {
+    /// One version. An entry without a commit blocks every build of that
+    /// `maj.min.rev-extra`; an entry with a commit blocks only that build.
+    Exact(SemVer),
+    /// Every version in the range.
+    Range(VersionRange),
+}
+
+impl BlockEntry {
+    pub fn matches(&self, ver: &SemVer) -> bool {
+        match self {
+            BlockEntry::Exact(blocked) if blocked.commit.is_some() => blocked.eq_exact(ver),
+            BlockEntry::Exact(blocked) => blocked.cmp(ver).is_eq(),
+            BlockEntry::Range(range) => range.contains(ver),
+        }
+    }
+    /// Length of this entry's binary form.
+    pub fn serialized_len(&self) -> usize {
+        4 + match self {
+            BlockEntry::Exact(_) => SemVer::SERIALIZED_LEN,
+            BlockEntry::Range(_) => VersionRange::SERIALIZED_LEN,
+        }
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_len();
+        let dest = buf.get_mut(..needed).ok_or(Error::BufferTooSmall { needed })?;
+        match self {
+            BlockEntry::Exact(ver) => {
+                dest[..4].copy_from_slice(&EXACT.to_le_bytes());
+                dest[4..].copy_from_slice(&<[u8; 16]>::from(ver));
+            }
+            BlockEntry::Range(range) => {
+                dest[..4].copy_from_slice(&RANGE.to_le_bytes());
+                range.write_to(&mut dest[4..])?;
+            }
+        }
+        Ok(needed)
+    }
+    /// Reads the binary form from the start of `buf`, returning the entry and its length.
+    pub fn read_from(buf: &[u8]) -> Result<(BlockEntry, usize), Error> {
+        let (kind, rest) = buf.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let entry = match u32::from_le_bytes(*kind) {
+            EXACT => BlockEntry::Exact(SemVer::from_bytes_strict(rest.first_chunk().ok_or(Error::Truncated)?)?),
+            RANGE => BlockEntry::Range(VersionRange::read_from(rest)?),
+            _ => return Err(Error::Malformed("unknown blocklist entry kind")),
+        };
+        Ok((entry, entry.serialized_len()))
+    }
+}
+
+/// A list of blocked versions and ranges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Blocklist<'a> {
+    entries: Entries<'a>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entries<'a> {
+    Table(&'a [BlockEntry]),
+    // a serialized list whose entries have all been validated
+    Serialized { count: usize, bytes: &'a [u8] },
+}
+
+impl<'a> Blocklist<'a> {
+    pub const fn new(entries: &'a [BlockEntry]) -> Self {
+        Blocklist { entries: Entries::Table(entries) }
+    }
+    /// Parses a serialized list in place. Every entry is validated up front, so
+    /// `is_blocked` cannot fail later.
+    pub fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        let (count, rest) = buf.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let count = u32::from_le_bytes(*count) as usize;
+        let mut at = 0;
+        for _ in 0..count {
+            at += BlockEntry::read_from(rest.get(at..).ok_or(Error::Truncated)?)?.1;
+        }
+        Ok(Blocklist { entries: Entries::Serialized { count, bytes: rest.get(..at).ok_or(Error::Truncated)? } })
+    }
+    /// Length of the binary form.
+    pub fn serialized_len(&self) -> usize {
+        4 + self.entries().map(|entry| entry.serialized_len()).sum::<usize>()
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_len();
+        let dest = buf.get_mut(..needed).ok_or(Error::BufferTooSmall { needed })?;
+        dest[..4].copy_from_slice(&(self.len() as u32).to_le_bytes());
+        let mut at = 4;
+        for entry in self.entries() {
+            at += entry.write_to(&mut dest[at..])?;
+        }
+        Ok(needed)
+    }
+    pub fn len(&self) -> usize {
+        match self.entries {
+            Entries::Table(entries) => entries.len(),
+            Entries::Serialized { count, .. } => count,
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The entries, in order.
+    pub fn entries(&self) -> impl Iterator<Item = BlockEntry> + 'a {
+        let (table, mut bytes) = match self.entries {
+            Entries::Table(entries) => (entries, &[][..]),
+            Entries::Serialized { bytes, .. } => (&[][..], bytes),
+        };
+        table.iter().copied().chain(core::iter::from_fn(move || {
+            if bytes.is_empty() {
+                return None;
+            }
+            let (entry, len) = BlockEntry::read_from(bytes).unwrap();
+            bytes = &bytes[len..];
+            Some(entry)
+        }))
+    }
+    /// True if any entry covers `ver`.
+    pub fn is_blocked(&self, ver: &SemVer) -> bool {
+        self.entries().any(|entry| entry.matches(ver))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_blocklist() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let entries = [
+            BlockEntry::Exact(v("v0.9.8")),
+            BlockEntry::Exact(v("v0.9.9-12-gabcd1234")),
+            BlockEntry::Range(VersionRange::half_open(v("v0.9.10"), v("v0.9.12"))),
+        ];
+        let list = Blocklist::new(&entries);
+        assert!(list.is_blocked(&v("v0.9.8")));
+        assert!(list.is_blocked(&v("v0.9.8-0-g12345678")));
+        assert!(!list.is_blocked(&v("v0.9.8-1")));
+        assert!(list.is_blocked(&v("v0.9.9-12-gabcd1234")));
+        assert!(!list.is_blocked(&v("v0.9.9-12-g12345678")));
+        assert!(!list.is_blocked(&v("v0.9.9-12")));
+        assert!(list.is_blocked(&v("v0.9.11-40")));
+        assert!(!list.is_blocked(&v("v0.9.12")));
+
+        let mut buf = [0u8; 4 + 20 + 20 + 40];
+        assert_eq!(list.serialized_len(), buf.len());
+        assert_eq!(list.write_to(&mut buf), Ok(buf.len()));
+        let parsed = Blocklist::read_from(&buf).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert!(parsed.entries().eq(entries.iter().copied()));
+        assert!(parsed.is_blocked(&v("v0.9.11-40")));
+        assert!(!parsed.is_blocked(&v("v0.9.12")));
+
+        assert_eq!(Blocklist::read_from(&buf[..buf.len() - 1]), Err(Error::Truncated));
+        assert_eq!(list.write_to(&mut buf[..50]), Err(Error::BufferTooSmall { needed: 84 }));
+        buf[24] = 2;
+        assert_eq!(Blocklist::read_from(&buf), Err(Error::Malformed("unknown blocklist entry kind")));
+        assert!(Blocklist::read_from(&[0; 4]).unwrap().is_empty());
+    }
+}
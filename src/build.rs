@@ -0,0 +1,307 @@
+//! Helpers for `build.rs`, so that downstream crates can capture the build version with
+//! a couple of lines:
+//!
+//! ```no_run
+//! // in build.rs's main()
+//! xous_semver::build::emit_cargo_env("XOUS_SEMVER").unwrap();
+//! ```
+//!
+//! after which `env!("XOUS_SEMVER")` holds e.g. `v0.9.8-760-gabcd1234`, and
+//! `SemVer::from_hex(env!("XOUS_SEMVER_HEX"))` recovers the 16-byte form.
+//!
+//! To avoid any parsing on the device, generate constants instead:
+//!
+//! ```no_run
+//! // in build.rs's main()
+//! xous_semver::build::generate_version_rs(std::env::var("OUT_DIR").unwrap()).unwrap();
+//! ```
+//!
+//! and `include!(concat!(env!("OUT_DIR"), "/version.rs"));` in the crate, which must also
+//! depend on `xous-semver` (not just build-depend on it) for the `SemVer` type.
+//!
+//! To catch a `Cargo.toml` version that wasn't bumped along with the release tag:
+//!
+//! ```no_run
+//! use xous_semver::build::{check_manifest_matches_tag, ManifestMatch};
+//! // in build.rs's main(); use `.unwrap()` instead to make this an error
+//! if let Err(e) = check_manifest_matches_tag(ManifestMatch::Exact) {
+//!     println!("cargo:warning={}", e);
+//! }
+//! ```
+use std::fmt::{self, Write as _};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::{BuildInfo, GitError, GitInfo, ParseError, SemVer};
+
+/// Describes the current repository and emits `cargo:rustc-env={prefix}=<version>` and
+/// `cargo:rustc-env={prefix}_HEX=<serialized bytes as hex>`, along with the
+/// `cargo:rerun-if-changed` lines that rerun the build script when HEAD moves. Returns
+/// the version.
+pub fn emit_cargo_env(prefix: &str) -> Result<SemVer, BuildError> {
+    let version = SemVer::from_git().map_err(BuildError::Git)?;
+    let paths = git_watch_paths().map_err(BuildError::Git)?;
+    let mut out = io::stdout().lock();
+    write_cargo_env(&mut out, prefix, &version)
+        .and_then(|()| write_rerun_if_changed(&mut out, &paths))
+        .map_err(|e| BuildError::Io(e.kind()))?;
+    Ok(version)
+}
+
+/// Describes the current repository and writes `version.rs` into `out_dir`, defining
+/// `VERSION: SemVer` and `VERSION_BYTES: [u8; 16]`, then asks cargo to rerun the build
+/// script when HEAD moves. Returns the version.
+pub fn generate_version_rs<P: AsRef<Path>>(out_dir: P) -> Result<SemVer, BuildError> {
+    let version = SemVer::from_git().map_err(BuildError::Git)?;
+    std::fs::write(out_dir.as_ref().join("version.rs"), version_rs(&version))
+        .map_err(|e| BuildError::Io(e.kind()))?;
+    write_rerun_if_changed(&mut io::stdout().lock(), &git_watch_paths().map_err(BuildError::Git)?)
+        .map_err(|e| BuildError::Io(e.kind()))?;
+    Ok(version)
+}
+
+/// Why `emit_cargo_env` or `generate_version_rs` failed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuildError {
+    Git(GitError),
+    /// The build output, such as `version.rs` or the `cargo:` lines, could not be written.
+    Io(io::ErrorKind),
+}
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::Git(e) => write!(f, "{}", e),
+            BuildError::Io(kind) => write!(f, "failed to write build output: {}", kind),
+        }
+    }
+}
+impl std::error::Error for BuildError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BuildError::Git(e) => Some(e),
+            BuildError::Io(_) => None,
+        }
+    }
+}
+
+// the files that change when HEAD moves: HEAD itself, and the branch it points at
+// (or `packed-refs`, if the branch has been packed). Empty outside a repository.
+fn git_watch_paths() -> Result<Vec<PathBuf>, GitError> {
+    let git = |args: &[&str]| -> Result<Option<String>, GitError> {
+        let output = Command::new("git").args(args).output().map_err(|e| GitError::Exec(e.kind()))?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        Ok(Some(stdout).filter(|out| output.status.success() && !out.trim().is_empty()))
+    };
+    let Some(dirs) = git(&["rev-parse", "--git-dir", "--git-common-dir"])? else {
+        return Ok(Vec::new());
+    };
+    let mut dirs = dirs.lines().map(PathBuf::from);
+    let (Some(git_dir), Some(common_dir)) = (dirs.next(), dirs.next()) else {
+        return Ok(Vec::new());
+    };
+    let mut paths = vec![git_dir.join("HEAD")];
+    // exits with status 1 on a detached HEAD, where HEAD holds the commit itself
+    if let Some(head_ref) = git(&["symbolic-ref", "-q", "HEAD"])? {
+        let loose = common_dir.join(head_ref.trim());
+        paths.push(if loose.exists() { loose } else { common_dir.join("packed-refs") });
+    }
+    Ok(paths)
+}
+
+fn write_rerun_if_changed<W: Write>(out: &mut W, paths: &[PathBuf]) -> io::Result<()> {
+    for path in paths {
+        writeln!(out, "cargo:rerun-if-changed={}", path.display())?;
+    }
+    Ok(())
+}
+
+fn version_rs(version: &SemVer) -> String {
+    let commit = match version.commit {
+        Some(commit) => format!("Some(0x{:08x})", commit),
+        None => "None".to_string(),
+    };
+    let mut bytes = String::new();
+    for (i, b) in <[u8; 16]>::from(version).iter().enumerate() {
+        let sep = if i == 0 { "" } else { ", " };
+        write!(bytes, "{}0x{:02x}", sep, b).unwrap();
+    }
+    format!(
+        "// generated by xous-semver from `{version}`; do not edit\n\
+        pub const VERSION: xous_semver::SemVer = xous_semver::SemVer {{ maj: {}, min: {}, rev: {}, extra: {}, commit: {} }};\n\
+        pub const VERSION_BYTES: [u8; 16] = [{}];\n",
+        version.maj, version.min, version.rev, version.extra, commit, bytes,
+    )
+}
+
+/// Captures a `BuildInfo` for the crate being built: the git version and dirty flag,
+/// `SOURCE_DATE_EPOCH` or else the current time, cargo's `TARGET`, and the version of
+/// the `RUSTC` cargo is using. Strings too long for the record are truncated.
+pub fn build_info() -> Result<BuildInfo, GitError> {
+    let git = GitInfo::from_git()?;
+    let timestamp = crate::source_date_epoch().unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0)
+    });
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let rustc = std::env::var_os("RUSTC")
+        .and_then(|rustc| std::process::Command::new(rustc).arg("--version").output().ok())
+        .map(|out| rustc_version(&String::from_utf8_lossy(&out.stdout)).to_string())
+        .unwrap_or_default();
+    Ok(BuildInfo::new(git.version, git.dirty, timestamp, truncate(&target, 36), truncate(&rustc, 16))
+        .expect("strings are truncated to fit"))
+}
+
+// `rustc 1.75.0 (82e1608df 2023-12-21)` -> `1.75.0`
+fn rustc_version(banner: &str) -> &str {
+    banner.split_whitespace().nth(1).unwrap_or("")
+}
+
+fn truncate(s: &str, max: usize) -> &str {
+    let mut end = s.len().min(max);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// How closely the `Cargo.toml` version must follow the latest reachable tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestMatch {
+    /// `maj.min.rev` must all match.
+    Exact,
+    /// Only `maj.min` must match, for crates that bump the manifest per minor release.
+    MajorMinor,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManifestError {
+    Git(GitError),
+    /// `CARGO_PKG_VERSION` is unset (not running under cargo) or not a plain `MAJ.MIN.REV`.
+    Manifest(Option<ParseError>),
+    Mismatch { manifest: SemVer, tag: SemVer },
+}
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Git(e) => write!(f, "{}", e),
+            ManifestError::Manifest(None) => write!(f, "CARGO_PKG_VERSION is not set"),
+            ManifestError::Manifest(Some(e)) => write!(f, "CARGO_PKG_VERSION: {}", e),
+            ManifestError::Mismatch { manifest, tag } => write!(
+                f, "Cargo.toml version {}.{}.{} does not match tag v{}.{}.{}",
+                manifest.maj, manifest.min, manifest.rev, tag.maj, tag.min, tag.rev
+            ),
+        }
+    }
+}
+impl std::error::Error for ManifestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ManifestError::Git(e) => Some(e),
+            ManifestError::Manifest(Some(e)) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// Checks that `CARGO_PKG_VERSION` agrees with the latest tag reachable from HEAD.
+/// Commits made since the tag are not a mismatch.
+pub fn check_manifest_matches_tag(mode: ManifestMatch) -> Result<(), ManifestError> {
+    let tag = SemVer::from_git().map_err(ManifestError::Git)?;
+    let manifest = std::env::var("CARGO_PKG_VERSION").map_err(|_| ManifestError::Manifest(None))?;
+    manifest_matches(&manifest, &tag, mode)
+}
+
+fn manifest_matches(manifest: &str, tag: &SemVer, mode: ManifestMatch) -> Result<(), ManifestError> {
+    let manifest = SemVer::from_cargo_version(manifest).map_err(|e| ManifestError::Manifest(Some(e)))?;
+    let matches = manifest.maj == tag.maj && manifest.min == tag.min
+        && (mode == ManifestMatch::MajorMinor || manifest.rev == tag.rev);
+    if matches {
+        Ok(())
+    } else {
+        Err(ManifestError::Mismatch { manifest, tag: *tag })
+    }
+}
+
+fn write_cargo_env<W: Write>(out: &mut W, prefix: &str, version: &SemVer) -> io::Result<()> {
+    writeln!(out, "cargo:rustc-env={}={}", prefix, version)?;
+    out.write_all(format!("cargo:rustc-env={}_HEX=", prefix).as_bytes())?;
+    out.write_all(&version.to_hex())?;
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_cargo_env() {
+        let mut out = Vec::new();
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        write_cargo_env(&mut out, "XOUS_SEMVER", &ver).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let mut lines = out.lines();
+        assert_eq!(lines.next(), Some("cargo:rustc-env=XOUS_SEMVER=v0.9.8-760-gabcd1234"));
+        let hex = lines.next().unwrap().strip_prefix("cargo:rustc-env=XOUS_SEMVER_HEX=").unwrap();
+        assert_eq!(SemVer::from_hex(hex), Ok(ver));
+        assert_eq!(lines.next(), None);
+    }
+    #[test]
+    fn test_build_error() {
+        let e = BuildError::Io(io::ErrorKind::PermissionDenied);
+        assert_eq!(e.to_string(), "failed to write build output: permission denied");
+        assert!(std::error::Error::source(&e).is_none());
+        assert!(std::error::Error::source(&BuildError::Git(GitError::NoTags)).is_some());
+    }
+    #[test]
+    fn test_rerun_if_changed() {
+        let paths = git_watch_paths().unwrap();
+        assert!(paths[0].ends_with("HEAD") && paths[0].exists());
+        let mut out = Vec::new();
+        write_rerun_if_changed(&mut out, &[PathBuf::from(".git/HEAD"), PathBuf::from(".git/refs/heads/main")]).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "cargo:rerun-if-changed=.git/HEAD\ncargo:rerun-if-changed=.git/refs/heads/main\n"
+        );
+    }
+    #[test]
+    fn test_manifest_matches() {
+        let tag = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(manifest_matches("0.9.8", &tag, ManifestMatch::Exact), Ok(()));
+        assert_eq!(
+            manifest_matches("0.9.7", &tag, ManifestMatch::Exact),
+            Err(ManifestError::Mismatch { manifest: SemVer::new(0, 9, 7), tag })
+        );
+        assert_eq!(manifest_matches("0.9.7", &tag, ManifestMatch::MajorMinor), Ok(()));
+        assert!(manifest_matches("0.8.8", &tag, ManifestMatch::MajorMinor).is_err());
+        assert_eq!(
+            manifest_matches("0.9.8-rc.1", &tag, ManifestMatch::Exact),
+            Err(ManifestError::Manifest(Some(ParseError::FieldCount)))
+        );
+        assert_eq!(
+            ManifestError::Mismatch { manifest: SemVer::new(0, 9, 7), tag }.to_string(),
+            "Cargo.toml version 0.9.7 does not match tag v0.9.8"
+        );
+    }
+    #[test]
+    fn test_build_info_helpers() {
+        assert_eq!(rustc_version("rustc 1.75.0 (82e1608df 2023-12-21)\n"), "1.75.0");
+        assert_eq!(rustc_version("rustc 1.77.0-nightly (5518eaa94 2024-01-29)"), "1.77.0-nightly");
+        assert_eq!(rustc_version(""), "");
+        assert_eq!(truncate("riscv32imac", 5), "riscv");
+        assert_eq!(truncate("ab\u{e9}", 3), "ab");
+        assert_eq!(truncate("ab", 5), "ab");
+    }
+    #[test]
+    fn test_version_rs() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(
+            version_rs(&ver),
+            "// generated by xous-semver from `v0.9.8-760-gabcd1234`; do not edit\n\
+            pub const VERSION: xous_semver::SemVer = xous_semver::SemVer { maj: 0, min: 9, rev: 8, extra: 760, commit: Some(0xabcd1234) };\n\
+            pub const VERSION_BYTES: [u8; 16] = [0x00, 0x00, 0x09, 0x00, 0x08, 0x00, 0xf8, 0x02, \
+            0x34, 0x12, 0xcd, 0xab, 0x01, 0x00, 0x00, 0x00];\n"
+        );
+        assert!(version_rs(&SemVer::new(1, 2, 3)).contains("extra: 0, commit: None }"));
+    }
+}
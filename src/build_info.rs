@@ -0,0 +1,144 @@
+//! A build record bundling the version with how and when the image was built.
+//!
+//! The binary layout, 80 bytes in total, little-endian:
+//!
+//! | offset | size | field |
+//! |--------|------|-------|
+//! | 0      | 16   | `SemVer` serialization |
+//! | 16     | 4    | flags; bit 0 is set for a dirty tree, other bits are zero |
+//! | 20     | 8    | build timestamp, seconds since the unix epoch |
+//! | 28     | 36   | target triple, UTF-8, zero-padded |
+//! | 64     | 16   | rustc version, UTF-8, zero-padded |
+use core::fmt;
+use crate::{Error, SemVer};
+
+const TARGET_LEN: usize = 36;
+const RUSTC_LEN: usize = 16;
+const FLAG_DIRTY: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuildInfo {
+    /// The version, including the commit if there is one.
+    pub version: SemVer,
+    /// Built from a working tree with uncommitted changes.
+    pub dirty: bool,
+    /// Seconds since the unix epoch.
+    pub timestamp: u64,
+    target: [u8; TARGET_LEN],
+    rustc: [u8; RUSTC_LEN],
+}
+
+impl BuildInfo {
+    /// Length of the binary record.
+    pub const LEN: usize = 80;
+
+    /// Fails with `Error::Overflow` if `target` is longer than 36 bytes or `rustc` (the
+    /// bare version, such as `1.75.0-nightly`) longer than 16.
+    pub fn new(version: SemVer, dirty: bool, timestamp: u64, target: &str, rustc: &str) -> Result<Self, Error> {
+        Ok(BuildInfo {
+            version,
+            dirty,
+            timestamp,
+            target: padded(target).ok_or(Error::Overflow("target"))?,
+            rustc: padded(rustc).ok_or(Error::Overflow("rustc"))?,
+        })
+    }
+    /// The target triple, e.g. `riscv32imac-unknown-xous-elf`.
+    pub fn target(&self) -> &str {
+        unpadded(&self.target).unwrap_or("")
+    }
+    /// The rustc version, e.g. `1.75.0`.
+    pub fn rustc(&self) -> &str {
+        unpadded(&self.rustc).unwrap_or("")
+    }
+    /// Writes the record to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..BuildInfo::LEN).ok_or(Error::BufferTooSmall { needed: BuildInfo::LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.version));
+        dest[16..20].copy_from_slice(&(if self.dirty { FLAG_DIRTY } else { 0 }).to_le_bytes());
+        dest[20..28].copy_from_slice(&self.timestamp.to_le_bytes());
+        dest[28..64].copy_from_slice(&self.target);
+        dest[64..80].copy_from_slice(&self.rustc);
+        Ok(BuildInfo::LEN)
+    }
+    /// Reads a record from the start of `buf`, rejecting unknown flags and strings that
+    /// aren't UTF-8.
+    pub fn read_from(buf: &[u8]) -> Result<BuildInfo, Error> {
+        let (version, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (flags, rest) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let (timestamp, rest) = rest.split_first_chunk::<8>().ok_or(Error::Truncated)?;
+        let (target, rest) = rest.split_first_chunk::<TARGET_LEN>().ok_or(Error::Truncated)?;
+        let (rustc, _) = rest.split_first_chunk::<RUSTC_LEN>().ok_or(Error::Truncated)?;
+        let version = SemVer::from_bytes_strict(version)?;
+        let flags = u32::from_le_bytes(*flags);
+        if flags & !FLAG_DIRTY != 0 {
+            return Err(Error::Malformed("unknown build flags"));
+        }
+        if unpadded(target).is_none() || unpadded(rustc).is_none() {
+            return Err(Error::Malformed("build string is not UTF-8"));
+        }
+        Ok(BuildInfo {
+            version,
+            dirty: flags & FLAG_DIRTY != 0,
+            timestamp: u64::from_le_bytes(*timestamp),
+            target: *target,
+            rustc: *rustc,
+        })
+    }
+}
+
+pub(crate) fn padded<const N: usize>(s: &str) -> Option<[u8; N]> {
+    let mut out = [0u8; N];
+    out.get_mut(..s.len())?.copy_from_slice(s.as_bytes());
+    Some(out)
+}
+
+pub(crate) fn unpadded(bytes: &[u8]) -> Option<&str> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    core::str::from_utf8(&bytes[..end]).ok()
+}
+
+impl fmt::Display for BuildInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.version)?;
+        if self.dirty {
+            write!(f, "-dirty")?;
+        }
+        write!(f, " built {} for {} with rustc {}", self.timestamp, self.target(), self.rustc())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_build_info() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let info = BuildInfo::new(ver, true, 1_700_000_000, "riscv32imac-unknown-xous-elf", "1.75.0").unwrap();
+        assert_eq!(info.target(), "riscv32imac-unknown-xous-elf");
+        assert_eq!(info.rustc(), "1.75.0");
+        assert_eq!(
+            info.to_string(),
+            "v0.9.8-760-gabcd1234-dirty built 1700000000 for riscv32imac-unknown-xous-elf with rustc 1.75.0"
+        );
+
+        let mut buf = [0u8; 84];
+        assert_eq!(info.write_to(&mut buf), Ok(80));
+        assert_eq!(buf[..16], <[u8; 16]>::from(ver));
+        assert_eq!(buf[16..20], [1, 0, 0, 0]);
+        assert_eq!(buf[28..32], *b"risc");
+        assert_eq!(BuildInfo::read_from(&buf), Ok(info));
+        assert_eq!(BuildInfo::read_from(&buf[..79]), Err(Error::Truncated));
+        assert_eq!(info.write_to(&mut buf[..79]), Err(Error::BufferTooSmall { needed: 80 }));
+
+        buf[16] = 2;
+        assert_eq!(BuildInfo::read_from(&buf), Err(Error::Malformed("unknown build flags")));
+        buf[16] = 0;
+        buf[64] = 0xff;
+        assert_eq!(BuildInfo::read_from(&buf), Err(Error::Malformed("build string is not UTF-8")));
+
+        assert_eq!(BuildInfo::new(ver, false, 0, "x", "1.75.0-nightly-2023-11-01"), Err(Error::Overflow("rustc")));
+        let exact = "a".repeat(36);
+        assert_eq!(BuildInfo::new(ver, false, 0, &exact, "").unwrap().target(), exact);
+    }
+}
@@ -0,0 +1,46 @@
+//! Computing the next version. Every bump clears the commit, since the bumped version
+//! doesn't exist yet and so has no commit of its own.
+use crate::{Error, SemVer};
+
+impl SemVer {
+    /// `maj + 1`, with `min`, `rev` and `extra` reset to 0.
+    pub fn bump_major(&self) -> Result<SemVer, Error> {
+        Ok(SemVer::new(self.maj.checked_add(1).ok_or(Error::Overflow("maj"))?, 0, 0))
+    }
+    /// `min + 1`, with `rev` and `extra` reset to 0.
+    pub fn bump_minor(&self) -> Result<SemVer, Error> {
+        Ok(SemVer::new(self.maj, self.min.checked_add(1).ok_or(Error::Overflow("min"))?, 0))
+    }
+    /// `rev + 1`, with `extra` reset to 0.
+    pub fn bump_rev(&self) -> Result<SemVer, Error> {
+        Ok(SemVer::new(self.maj, self.min, self.rev.checked_add(1).ok_or(Error::Overflow("rev"))?))
+    }
+    /// `extra + 1`, keeping `maj.min.rev`.
+    pub fn bump_extra(&self) -> Result<SemVer, Error> {
+        Ok(SemVer {
+            extra: self.extra.checked_add(1).ok_or(Error::Overflow("extra"))?,
+            commit: None,
+            ..*self
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_bump() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.bump_major(), Ok(SemVer::new(1, 0, 0)));
+        assert_eq!(ver.bump_minor(), Ok(SemVer::new(0, 10, 0)));
+        assert_eq!(ver.bump_rev(), Ok(SemVer::new(0, 9, 9)));
+        assert_eq!(ver.bump_extra(), Ok(SemVer::from_str("v0.9.8-761").unwrap()));
+
+        let max = SemVer { extra: u16::MAX, ..SemVer::new(u16::MAX, u16::MAX, u16::MAX) };
+        assert_eq!(max.bump_major(), Err(Error::Overflow("maj")));
+        assert_eq!(max.bump_minor(), Err(Error::Overflow("min")));
+        assert_eq!(max.bump_rev(), Err(Error::Overflow("rev")));
+        assert_eq!(max.bump_extra(), Err(Error::Overflow("extra")));
+        assert_eq!(SemVer::new(0, u16::MAX, 3).bump_major(), Ok(SemVer::new(1, 0, 0)));
+    }
+}
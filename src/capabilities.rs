@@ -0,0 +1,140 @@
+//! Which firmware version introduced each optional feature, so checks like "does the
+//! EC support command X?" read from one table instead of scattered comparisons.
+//!
+//! Features are bits 0 to 63, which callers name with their own constants. The binary
+//! form is a little-endian entry count, then each entry: the feature bit as a
+//! little-endian u32 and the 16-byte serialization of the first supporting version,
+//! 20 bytes per entry.
+use crate::table::{Record, Table};
+use crate::{Error, SemVer};
+
+/// One feature and the first version that supports it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capability {
+    pub bit: u8,
+    pub since: SemVer,
+}
+
+impl Capability {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 4 + SemVer::SERIALIZED_LEN;
+
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    /// A `bit` of 64 or more fails with `Error::Overflow`, as `read_from` would reject it.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.bit >= 64 {
+            return Err(Error::Overflow("feature bit"));
+        }
+        let dest = buf.get_mut(..Capability::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: Capability::SERIALIZED_LEN })?;
+        dest[..4].copy_from_slice(&(self.bit as u32).to_le_bytes());
+        dest[4..].copy_from_slice(&<[u8; 16]>::from(&self.since));
+        Ok(Capability::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<Capability, Error> {
+        let (bit, rest) = buf.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let (since, _) = rest.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let bit = u32::from_le_bytes(*bit);
+        if bit >= 64 {
+            return Err(Error::Overflow("feature bit"));
+        }
+        Ok(Capability { bit: bit as u8, since: SemVer::from_bytes_strict(since)? })
+    }
+}
+
+impl Record for Capability {
+    const LEN: usize = Capability::SERIALIZED_LEN;
+    fn write_record(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.write_to(buf)
+    }
+    fn read_record(buf: &[u8]) -> Result<Self, Error> {
+        Capability::read_from(buf)
+    }
+}
+
+/// A table of features and the versions that introduced them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities<'a> {
+    entries: Table<'a, Capability>,
+}
+
+impl<'a> Capabilities<'a> {
+    pub const fn new(entries: &'a [Capability]) -> Self {
+        Capabilities { entries: Table::Slice(entries) }
+    }
+    /// Parses a serialized table in place. Every entry is validated up front, so lookups
+    /// cannot fail later.
+    pub fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        Ok(Capabilities { entries: Table::read_from(buf)? })
+    }
+    /// Length of the binary form.
+    pub fn serialized_len(&self) -> usize {
+        self.entries.serialized_len()
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.entries.write_to(buf)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The entries, in order.
+    pub fn entries(&self) -> impl Iterator<Item = Capability> + 'a {
+        self.entries.iter()
+    }
+    /// True if firmware `version` supports `feature`. Features missing from the table
+    /// are unsupported.
+    pub fn supports(&self, feature: u8, version: &SemVer) -> bool {
+        self.entries().any(|entry| entry.bit == feature && *version >= entry.since)
+    }
+    /// Every feature `version` supports, as a bit mask.
+    pub fn supported(&self, version: &SemVer) -> u64 {
+        self.entries()
+            .filter(|entry| entry.bit < 64 && *version >= entry.since)
+            .fold(0, |mask, entry| mask | 1 << entry.bit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    const CMD_WIFI_SCAN: u8 = 0;
+    const CMD_BATTERY_STATS: u8 = 5;
+    const CMD_UNLISTED: u8 = 9;
+    #[test]
+    fn test_capabilities() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let entries = [
+            Capability { bit: CMD_WIFI_SCAN, since: v("v0.9.3") },
+            Capability { bit: CMD_BATTERY_STATS, since: v("v0.9.8-100") },
+        ];
+        let ec = Capabilities::new(&entries);
+        let old = v("v0.9.8-99-gabcd1234");
+        assert!(ec.supports(CMD_WIFI_SCAN, &old));
+        assert!(!ec.supports(CMD_BATTERY_STATS, &old));
+        assert!(ec.supports(CMD_BATTERY_STATS, &v("v0.9.8-100")));
+        assert!(!ec.supports(CMD_UNLISTED, &v("v9.9.9")));
+        assert_eq!(ec.supported(&old), 0b1);
+        assert_eq!(ec.supported(&v("v1.0.0")), 0b10_0001);
+        assert_eq!(ec.supported(&v("v0.1.0")), 0);
+
+        let mut buf = [0u8; 44];
+        assert_eq!(ec.serialized_len(), 44);
+        assert_eq!(ec.write_to(&mut buf), Ok(44));
+        let parsed = Capabilities::read_from(&buf).unwrap();
+        assert!(parsed.entries().eq(entries.iter().copied()));
+        assert!(parsed.supports(CMD_BATTERY_STATS, &v("v0.9.9")));
+
+        assert_eq!(Capabilities::read_from(&buf[..43]), Err(Error::Truncated));
+        assert_eq!(ec.write_to(&mut buf[..43]), Err(Error::BufferTooSmall { needed: 44 }));
+        buf[24] = 64;
+        assert_eq!(Capabilities::read_from(&buf), Err(Error::Overflow("feature bit")));
+        // a table built in code can't serialize a bit that wouldn't read back
+        let bad = [Capability { bit: 64, since: v("v1.0.0") }];
+        assert_eq!(Capabilities::new(&bad).write_to(&mut buf), Err(Error::Overflow("feature bit")));
+    }
+}
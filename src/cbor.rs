@@ -0,0 +1,67 @@
+//! `minicbor` support. A version is encoded as a CBOR array of its numeric fields,
+//! `[maj, min, rev, extra]`, with the commit appended as a fifth element when present.
+//! Decoders skip any elements past the fifth, leaving room for later additions.
+use minicbor::decode::{self, Decoder};
+use minicbor::encode::{self, Encoder, Write};
+use minicbor::{Decode, Encode};
+use crate::SemVer;
+
+impl<C> Encode<C> for SemVer {
+    fn encode<W: Write>(&self, e: &mut Encoder<W>, _ctx: &mut C) -> Result<(), encode::Error<W::Error>> {
+        e.array(if self.commit.is_some() { 5 } else { 4 })?
+            .u16(self.maj)?
+            .u16(self.min)?
+            .u16(self.rev)?
+            .u16(self.extra)?;
+        if let Some(commit) = self.commit {
+            e.u32(commit)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'b, C> Decode<'b, C> for SemVer {
+    fn decode(d: &mut Decoder<'b>, _ctx: &mut C) -> Result<Self, decode::Error> {
+        let len = d.array()?.ok_or_else(|| decode::Error::message("indefinite-length version array"))?;
+        if len < 4 {
+            return Err(decode::Error::message("version array too short"));
+        }
+        let ver = SemVer {
+            maj: d.u16()?,
+            min: d.u16()?,
+            rev: d.u16()?,
+            extra: d.u16()?,
+            commit: if len > 4 { Some(d.u32()?) } else { None },
+        };
+        for _ in 5..len {
+            d.skip()?;
+        }
+        Ok(ver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_cbor() {
+        let mut buf = [0u8; 32];
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        minicbor::encode(ver, &mut buf[..]).unwrap();
+        assert_eq!(buf[..13], [0x85, 0x00, 0x09, 0x08, 0x19, 0x02, 0xf8, 0x1a, 0xab, 0xcd, 0x12, 0x34, 0x00]);
+        assert_eq!(minicbor::decode::<SemVer>(&buf).unwrap(), ver);
+
+        let ver = SemVer::from_str("v1.2.3").unwrap();
+        minicbor::encode(ver, &mut buf[..]).unwrap();
+        assert_eq!(buf[..5], [0x84, 0x01, 0x02, 0x03, 0x00]);
+        assert_eq!(minicbor::decode::<SemVer>(&buf).unwrap(), ver);
+
+        // a newer writer appended a field
+        assert_eq!(
+            minicbor::decode::<SemVer>(&[0x86, 0x01, 0x02, 0x03, 0x00, 0x01, 0xf5]).unwrap(),
+            SemVer::from_str("v1.2.3-0-g1").unwrap()
+        );
+        assert!(minicbor::decode::<SemVer>(&[0x83, 0x01, 0x02, 0x03]).is_err());
+        assert!(minicbor::decode::<SemVer>(&[0x84, 0x01, 0x02, 0x03]).is_err());
+    }
+}
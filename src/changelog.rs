@@ -0,0 +1,141 @@
+//! Release notes keyed by version, so the updater can show what's new across every
+//! release an update crosses.
+//!
+//! Entries are fixed-size so tables can live in flash without an allocator. An entry's
+//! binary form is 64 bytes: the 16-byte version serialization, a little-endian message
+//! ID, and up to 44 bytes of UTF-8 text, zero-padded. A table is a little-endian entry
+//! count followed by its entries.
+use crate::build_info::{padded, unpadded};
+use crate::table::{Record, Table};
+use crate::{Error, SemVer};
+
+const TEXT_LEN: usize = 44;
+
+/// One note: the release it belongs to, a message ID for localized UIs, and a short
+/// fallback text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangelogEntry {
+    pub version: SemVer,
+    pub message_id: u32,
+    text: [u8; TEXT_LEN],
+}
+
+impl ChangelogEntry {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 64;
+
+    /// Fails with `Error::Overflow` if `text` is longer than 44 bytes.
+    pub fn new(version: SemVer, message_id: u32, text: &str) -> Result<Self, Error> {
+        Ok(ChangelogEntry { version, message_id, text: padded(text).ok_or(Error::Overflow("text"))? })
+    }
+    pub fn text(&self) -> &str {
+        unpadded(&self.text).unwrap_or("")
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..ChangelogEntry::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: ChangelogEntry::SERIALIZED_LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.version));
+        dest[16..20].copy_from_slice(&self.message_id.to_le_bytes());
+        dest[20..].copy_from_slice(&self.text);
+        Ok(ChangelogEntry::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`, rejecting text that isn't UTF-8.
+    pub fn read_from(buf: &[u8]) -> Result<ChangelogEntry, Error> {
+        let (version, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (message_id, rest) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let (text, _) = rest.split_first_chunk::<TEXT_LEN>().ok_or(Error::Truncated)?;
+        if unpadded(text).is_none() {
+            return Err(Error::Malformed("changelog text is not UTF-8"));
+        }
+        Ok(ChangelogEntry {
+            version: SemVer::from_bytes_strict(version)?,
+            message_id: u32::from_le_bytes(*message_id),
+            text: *text,
+        })
+    }
+}
+
+impl Record for ChangelogEntry {
+    const LEN: usize = ChangelogEntry::SERIALIZED_LEN;
+    fn write_record(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.write_to(buf)
+    }
+    fn read_record(buf: &[u8]) -> Result<Self, Error> {
+        ChangelogEntry::read_from(buf)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Changelog<'a> {
+    entries: Table<'a, ChangelogEntry>,
+}
+
+impl<'a> Changelog<'a> {
+    pub const fn new(entries: &'a [ChangelogEntry]) -> Self {
+        Changelog { entries: Table::Slice(entries) }
+    }
+    /// Parses a serialized table in place. Every entry is validated up front, so lookups
+    /// cannot fail later.
+    pub fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        Ok(Changelog { entries: Table::read_from(buf)? })
+    }
+    /// Length of the binary form.
+    pub fn serialized_len(&self) -> usize {
+        self.entries.serialized_len()
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.entries.write_to(buf)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The entries, in table order.
+    pub fn entries(&self) -> impl Iterator<Item = ChangelogEntry> + 'a {
+        self.entries.iter()
+    }
+    /// What's new when updating from `from` to `to`: the entries for versions after
+    /// `from` up to and including `to`, in table order.
+    pub fn entries_between(&self, from: &SemVer, to: &SemVer) -> impl Iterator<Item = ChangelogEntry> + 'a {
+        let (from, to) = (*from, *to);
+        self.entries().filter(move |entry| entry.version > from && entry.version <= to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_changelog() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let entries = [
+            ChangelogEntry::new(v("v0.9.7"), 70, "New password manager").unwrap(),
+            ChangelogEntry::new(v("v0.9.8"), 80, "Faster wifi reconnect").unwrap(),
+            ChangelogEntry::new(v("v0.9.8"), 81, "Battery stats").unwrap(),
+            ChangelogEntry::new(v("v0.9.9"), 90, "Fix for suspend").unwrap(),
+        ];
+        let log = Changelog::new(&entries);
+        let ids = |from, to| log.entries_between(&v(from), &v(to)).map(|e| e.message_id).collect::<Vec<_>>();
+        assert_eq!(ids("v0.9.7", "v0.9.9"), [80, 81, 90]);
+        assert_eq!(ids("v0.9.7-12-gabcd1234", "v0.9.8-3"), [80, 81]);
+        assert_eq!(ids("v0.9.9", "v0.9.9"), [0u32; 0]);
+        assert_eq!(entries[1].text(), "Faster wifi reconnect");
+        assert_eq!(ChangelogEntry::new(v("v1.0.0"), 0, &"x".repeat(45)), Err(Error::Overflow("text")));
+
+        let mut buf = [0u8; 4 + 4 * 64];
+        assert_eq!(log.serialized_len(), buf.len());
+        assert_eq!(log.write_to(&mut buf), Ok(buf.len()));
+        let parsed = Changelog::read_from(&buf).unwrap();
+        assert!(parsed.entries().eq(entries.iter().copied()));
+        assert_eq!(parsed.entries_between(&v("v0.9.8"), &v("v1.0.0")).next().unwrap().text(), "Fix for suspend");
+
+        assert_eq!(Changelog::read_from(&buf[..buf.len() - 1]), Err(Error::Truncated));
+        assert_eq!(log.write_to(&mut buf[..100]), Err(Error::BufferTooSmall { needed: 260 }));
+        buf[4 + 20] = 0xff;
+        assert_eq!(Changelog::read_from(&buf), Err(Error::Malformed("changelog text is not UTF-8")));
+    }
+}
@@ -0,0 +1,64 @@
+//! A CRC-protected version record for storage in flash.
+//!
+//! The record is the 16-byte serialization followed by a little-endian CRC-32 over
+//! those 16 bytes, for 20 bytes in total.
+use crate::crc::crc32;
+use crate::{Error, SemVer};
+
+impl SemVer {
+    /// Length of the CRC-protected record.
+    pub const CHECKED_LEN: usize = SemVer::SERIALIZED_LEN + 4;
+
+    /// Writes the CRC-protected record to the start of `buf`, returning the number of
+    /// bytes written.
+    pub fn write_checked(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..SemVer::CHECKED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SemVer::CHECKED_LEN })?;
+        let ser: [u8; 16] = self.into();
+        dest[..16].copy_from_slice(&ser);
+        dest[16..].copy_from_slice(&crc32(&ser).to_le_bytes());
+        Ok(SemVer::CHECKED_LEN)
+    }
+    /// Reads a CRC-protected record from the start of `buf`. A checksum mismatch is
+    /// reported as `Error::Corrupt`; a record whose checksum matches but whose contents
+    /// are invalid is reported as `Error::Malformed`.
+    pub fn read_checked(buf: &[u8]) -> Result<SemVer, Error> {
+        let (ser, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (crc, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        if crc32(ser) != u32::from_le_bytes(*crc) {
+            return Err(Error::Corrupt);
+        }
+        SemVer::from_bytes_strict(ser)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_checked() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let mut buf = [0u8; 24];
+        assert_eq!(ver.write_checked(&mut buf), Ok(20));
+        let ser: [u8; 16] = (&ver).into();
+        assert_eq!(buf[..16], ser);
+        assert_eq!(u32::from_le_bytes(buf[16..20].try_into().unwrap()), crate::crc::crc32(&ser));
+        assert_eq!(SemVer::read_checked(&buf).unwrap(), ver);
+
+        assert_eq!(ver.write_checked(&mut buf[..19]), Err(Error::BufferTooSmall { needed: 20 }));
+        assert_eq!(SemVer::read_checked(&buf[..19]), Err(Error::Truncated));
+
+        let mut corrupted = buf;
+        corrupted[3] ^= 0x10;
+        assert_eq!(SemVer::read_checked(&corrupted), Err(Error::Corrupt));
+        // erased flash
+        assert_eq!(SemVer::read_checked(&[0xff; 20]), Err(Error::Corrupt));
+
+        // a checksum over an invalid record is still rejected, but as malformed
+        let mut bad = [0u8; 20];
+        bad[12] = 2;
+        let crc = crate::crc::crc32(&bad[..16]);
+        bad[16..].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(SemVer::read_checked(&bad), Err(Error::Malformed("invalid commit flag")));
+    }
+}
@@ -0,0 +1,102 @@
+//! The COM-bus packet the EC and SoC use to exchange versions, defined here so both
+//! sides build the same frame.
+//!
+//! The frame is 28 bytes, little-endian: the magic `COM_MAGIC`, the protocol revision,
+//! the 16-byte version serialization, the 4-byte `HwRev`, and a CRC-32 over the
+//! preceding 24 bytes. On the wire it goes as 14 16-bit COM words.
+use crate::crc::crc32;
+use crate::{Error, HwRev, SemVer};
+
+/// The first half-word of every version frame.
+pub const COM_MAGIC: u16 = 0x5653;
+/// The frame layout this crate reads and writes.
+pub const COM_PROTOCOL_REV: u16 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionPacket {
+    pub version: SemVer,
+    pub hw: HwRev,
+}
+
+impl VersionPacket {
+    /// Length of the frame in bytes.
+    pub const LEN: usize = 28;
+    /// Length of the frame in COM words.
+    pub const WORDS: usize = VersionPacket::LEN / 2;
+
+    pub fn encode(&self) -> [u8; VersionPacket::LEN] {
+        let mut frame = [0u8; VersionPacket::LEN];
+        frame[0..2].copy_from_slice(&COM_MAGIC.to_le_bytes());
+        frame[2..4].copy_from_slice(&COM_PROTOCOL_REV.to_le_bytes());
+        frame[4..20].copy_from_slice(&<[u8; 16]>::from(&self.version));
+        self.hw.write_to(&mut frame[20..24]).unwrap();
+        let crc = crc32(&frame[..24]);
+        frame[24..28].copy_from_slice(&crc.to_le_bytes());
+        frame
+    }
+    /// Decodes a frame, checking the magic, the protocol revision and the CRC. A CRC
+    /// mismatch is reported as `Error::Corrupt`.
+    pub fn decode(frame: &[u8]) -> Result<VersionPacket, Error> {
+        let frame = frame.get(..VersionPacket::LEN).ok_or(Error::Truncated)?;
+        if u16::from_le_bytes([frame[0], frame[1]]) != COM_MAGIC {
+            return Err(Error::Malformed("bad version packet magic"));
+        }
+        if u16::from_le_bytes([frame[2], frame[3]]) != COM_PROTOCOL_REV {
+            return Err(Error::Malformed("unsupported version packet revision"));
+        }
+        let (body, crc) = frame.split_last_chunk::<4>().ok_or(Error::Truncated)?;
+        if crc32(body) != u32::from_le_bytes(*crc) {
+            return Err(Error::Corrupt);
+        }
+        let (version, hw) = body.get(4..).and_then(|rest| rest.split_first_chunk::<16>()).ok_or(Error::Truncated)?;
+        Ok(VersionPacket {
+            version: SemVer::from_bytes_strict(version)?,
+            hw: HwRev::read_from(hw)?,
+        })
+    }
+    /// The frame as COM words, each two bytes little-endian.
+    pub fn encode_words(&self) -> [u16; VersionPacket::WORDS] {
+        let frame = self.encode();
+        let mut words = [0u16; VersionPacket::WORDS];
+        for (word, bytes) in words.iter_mut().zip(frame.chunks_exact(2)) {
+            *word = u16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        words
+    }
+    pub fn decode_words(words: &[u16]) -> Result<VersionPacket, Error> {
+        let words = words.get(..VersionPacket::WORDS).ok_or(Error::Truncated)?;
+        let mut frame = [0u8; VersionPacket::LEN];
+        for (bytes, word) in frame.chunks_exact_mut(2).zip(words) {
+            bytes.copy_from_slice(&word.to_le_bytes());
+        }
+        VersionPacket::decode(&frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_version_packet() {
+        let packet = VersionPacket { version: SemVer::from_str("v0.9.8-760-gabcd1234").unwrap(), hw: HwRev::new(3, 1, 0) };
+        let frame = packet.encode();
+        assert_eq!(frame[..4], [0x53, 0x56, 1, 0]);
+        assert_eq!(frame[20..24], [3, 1, 0, 0]);
+        assert_eq!(VersionPacket::decode(&frame), Ok(packet));
+
+        let words = packet.encode_words();
+        assert_eq!(words[0], COM_MAGIC);
+        assert_eq!(VersionPacket::decode_words(&words), Ok(packet));
+        assert_eq!(VersionPacket::decode_words(&words[..13]), Err(Error::Truncated));
+
+        let mut bad = frame;
+        bad[5] ^= 1;
+        assert_eq!(VersionPacket::decode(&bad), Err(Error::Corrupt));
+        bad = frame;
+        bad[2] = 2;
+        assert_eq!(VersionPacket::decode(&bad), Err(Error::Malformed("unsupported version packet revision")));
+        bad[0] = 0;
+        assert_eq!(VersionPacket::decode(&bad), Err(Error::Malformed("bad version packet magic")));
+        assert_eq!(VersionPacket::decode(&frame[..27]), Err(Error::Truncated));
+    }
+}
@@ -0,0 +1,237 @@
+//! Comparisons beyond the plain `Ord`, which looks at `maj.min.rev-extra` only.
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use crate::policy::OrderingPolicy;
+use crate::SemVer;
+
+/// What kind of change moving from one version to another is; see `SemVer::delta`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeKind {
+    MajorUpgrade,
+    MinorUpgrade,
+    PatchUpgrade,
+    /// Same `maj.min.rev`, but a newer `extra`, or the same `extra` with a different commit.
+    DevDrift,
+    Downgrade,
+    /// Equal, including the commit.
+    Identical,
+}
+
+/// The newest version and its position, or `None` if `versions` is empty. Ties go to
+/// the first occurrence (e.g. slot A over slot B). Commits are not compared.
+pub fn latest<I>(versions: I) -> Option<(usize, SemVer)>
+where
+    I: IntoIterator,
+    I::Item: Borrow<SemVer>,
+{
+    pick(versions, Ordering::Greater)
+}
+
+/// The oldest version and its position, or `None` if `versions` is empty. Ties go to
+/// the first occurrence.
+pub fn earliest<I>(versions: I) -> Option<(usize, SemVer)>
+where
+    I: IntoIterator,
+    I::Item: Borrow<SemVer>,
+{
+    pick(versions, Ordering::Less)
+}
+
+fn pick<I>(versions: I, wanted: Ordering) -> Option<(usize, SemVer)>
+where
+    I: IntoIterator,
+    I::Item: Borrow<SemVer>,
+{
+    let mut best: Option<(usize, SemVer)> = None;
+    for (i, ver) in versions.into_iter().enumerate() {
+        let ver = *ver.borrow();
+        match best {
+            Some((_, b)) if ver.cmp(&b) != wanted => {}
+            _ => best = Some((i, ver)),
+        }
+    }
+    best
+}
+
+/// A `SemVer` whose ordering also looks at the commit, giving a total order that agrees
+/// with `==`. Useful for sorting and deduplicating dev builds that share a tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitOrdered(pub SemVer);
+
+impl Ord for CommitOrdered {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp_with_commit(&other.0)
+    }
+}
+impl PartialOrd for CommitOrdered {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl From<SemVer> for CommitOrdered {
+    fn from(ver: SemVer) -> Self {
+        CommitOrdered(ver)
+    }
+}
+
+impl SemVer {
+    /// True if the two versions are interface-compatible under the usual semver (and
+    /// Cargo caret) rules: the same major for 1.0 and up, the same major and minor for
+    /// 0.x, and the same `maj.min.rev` for 0.0.x. `extra` and the commit are ignored.
+    pub fn compatible_with(&self, other: &SemVer) -> bool {
+        if self.maj != other.maj {
+            false
+        } else if self.maj > 0 {
+            true
+        } else if self.min != other.min {
+            false
+        } else {
+            self.min > 0 || self.rev == other.rev
+        }
+    }
+    /// Equal in `maj.min.rev-extra`, ignoring the commit; the equality that matches `Ord`.
+    pub fn eq_release(&self, other: &SemVer) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+    /// Equal in every field, including the commit. This is what `==` does.
+    pub fn eq_exact(&self, other: &SemVer) -> bool {
+        self == other
+    }
+    /// Classifies moving from `self` to `to`, by the most significant component that
+    /// increased.
+    pub fn delta(&self, to: &SemVer) -> UpgradeKind {
+        match self.cmp(to) {
+            Ordering::Greater => UpgradeKind::Downgrade,
+            Ordering::Equal if self == to => UpgradeKind::Identical,
+            Ordering::Equal => UpgradeKind::DevDrift,
+            Ordering::Less if to.maj != self.maj => UpgradeKind::MajorUpgrade,
+            Ordering::Less if to.min != self.min => UpgradeKind::MinorUpgrade,
+            Ordering::Less if to.rev != self.rev => UpgradeKind::PatchUpgrade,
+            Ordering::Less => UpgradeKind::DevDrift,
+        }
+    }
+    /// How many commits `self` is behind `other`, when both describe the same tag so
+    /// that `extra` counts commits since it; `None` for different tags. A version ahead
+    /// of `other` is 0 behind. This assumes both builds are on the same line of history.
+    pub fn commits_behind(&self, other: &SemVer) -> Option<u32> {
+        if (self.maj, self.min, self.rev) != (other.maj, other.min, other.rev) {
+            return None;
+        }
+        Some(u32::from(other.extra.saturating_sub(self.extra)))
+    }
+    /// Compares under the given policy instead of `Ord`.
+    pub fn cmp_with<P: OrderingPolicy + ?Sized>(&self, other: &SemVer, policy: &P) -> Ordering {
+        policy.cmp(self, other)
+    }
+    /// Like `cmp`, but breaks ties on the commit: no commit sorts first, then commits by
+    /// numeric value. Commit hashes carry no notion of age, so the tie-break is arbitrary,
+    /// but it is stable.
+    pub fn cmp_with_commit(&self, other: &SemVer) -> Ordering {
+        self.cmp(other).then(self.commit.cmp(&other.commit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{IgnoreExtra, ReleaseFirst, Standard, WithCommit};
+    #[test]
+    fn test_compatible_with() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        assert!(v("v1.2.3").compatible_with(&v("v1.9.0")));
+        assert!(v("v1.2.3-5-gabcd1234").compatible_with(&v("v1.0.0")));
+        assert!(!v("v1.2.3").compatible_with(&v("v2.2.3")));
+        assert!(v("v0.9.8-760").compatible_with(&v("v0.9.10")));
+        assert!(!v("v0.9.8").compatible_with(&v("v0.10.8")));
+        assert!(v("v0.0.3").compatible_with(&v("v0.0.3-1")));
+        assert!(!v("v0.0.3").compatible_with(&v("v0.0.4")));
+        assert!(!v("v0.1.0").compatible_with(&v("v1.1.0")));
+    }
+    #[test]
+    fn test_latest_earliest() {
+        let slots = [
+            SemVer::new(0, 9, 8),
+            SemVer::from_str("v0.9.9-3-gabcd1234").unwrap(),
+            SemVer::from_str("v0.9.9-3-g12345678").unwrap(),
+            SemVer::new(0, 9, 7),
+        ];
+        assert_eq!(latest(slots.iter()), Some((1, slots[1])));
+        assert_eq!(earliest(slots.iter()), Some((3, slots[3])));
+        assert_eq!(latest(slots.iter().copied().rev()), Some((1, slots[2])));
+        assert_eq!(latest(&[] as &[SemVer]), None);
+        assert_eq!(earliest(Vec::<SemVer>::new()), None);
+    }
+    #[test]
+    fn test_eq_release() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let tagged = v("v0.9.8");
+        let long = v("v0.9.8-0-gabcd1234");
+        assert!(tagged.eq_release(&long));
+        assert!(!tagged.eq_exact(&long));
+        assert_ne!(tagged, long);
+        assert!(long.eq_exact(&v("v0.9.8-gabcd1234")));
+        assert!(!tagged.eq_release(&v("v0.9.8-1")));
+    }
+    #[test]
+    fn test_delta() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let from = v("v0.9.8-760-gabcd1234");
+        assert_eq!(from.delta(&v("v1.0.0")), UpgradeKind::MajorUpgrade);
+        assert_eq!(from.delta(&v("v0.10.0")), UpgradeKind::MinorUpgrade);
+        assert_eq!(from.delta(&v("v0.9.9")), UpgradeKind::PatchUpgrade);
+        assert_eq!(from.delta(&v("v0.9.8-761")), UpgradeKind::DevDrift);
+        assert_eq!(from.delta(&v("v0.9.8-760-g00000001")), UpgradeKind::DevDrift);
+        assert_eq!(from.delta(&v("v0.9.8-760")), UpgradeKind::DevDrift);
+        assert_eq!(from.delta(&from), UpgradeKind::Identical);
+        assert_eq!(from.delta(&v("v0.9.8")), UpgradeKind::Downgrade);
+        assert_eq!(from.delta(&v("v0.9.7-9999")), UpgradeKind::Downgrade);
+    }
+    #[test]
+    fn test_commits_behind() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let mine = v("v0.9.8-760-gabcd1234");
+        assert_eq!(mine.commits_behind(&v("v0.9.8-800-g12345678")), Some(40));
+        assert_eq!(mine.commits_behind(&mine), Some(0));
+        assert_eq!(mine.commits_behind(&v("v0.9.8")), Some(0));
+        assert_eq!(v("v0.9.8").commits_behind(&mine), Some(760));
+        assert_eq!(mine.commits_behind(&v("v0.9.9-1")), None);
+    }
+    #[test]
+    fn test_cmp_with_policy() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let release = v("v0.9.8");
+        let dev = v("v0.9.8-760-gabcd1234");
+        let other_dev = v("v0.9.8-760-g00000001");
+        assert_eq!(release.cmp_with(&dev, &Standard), Ordering::Less);
+        assert_eq!(release.cmp_with(&dev, &IgnoreExtra), Ordering::Equal);
+        assert_eq!(other_dev.cmp_with(&dev, &WithCommit), Ordering::Less);
+        assert_eq!(release.cmp_with(&dev, &ReleaseFirst), Ordering::Greater);
+        assert_eq!(dev.cmp_with(&v("v0.9.9"), &ReleaseFirst), Ordering::Less);
+        assert_eq!(dev.cmp_with(&v("v0.9.8-761"), &ReleaseFirst), Ordering::Less);
+        let reversed = |a: &SemVer, b: &SemVer| b.cmp(a);
+        assert_eq!(release.cmp_with(&dev, &reversed), Ordering::Greater);
+        let dynamic: &dyn OrderingPolicy = &IgnoreExtra;
+        assert_eq!(release.cmp_with(&dev, dynamic), Ordering::Equal);
+
+        let mut builds = [dev, v("v0.9.9-1"), release, v("v0.9.7")];
+        builds.sort_by(|a, b| a.cmp_with(b, &ReleaseFirst));
+        assert_eq!(builds, [v("v0.9.7"), dev, release, v("v0.9.9-1")]);
+    }
+    #[test]
+    fn test_cmp_with_commit() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let a = v("v0.9.8-760-g00000001");
+        let b = v("v0.9.8-760-gabcd1234");
+        let plain = v("v0.9.8-760");
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+        assert_eq!(a.cmp_with_commit(&b), Ordering::Less);
+        assert_eq!(plain.cmp_with_commit(&a), Ordering::Less);
+        assert_eq!(b.cmp_with_commit(&v("v0.9.8-761-g00000000")), Ordering::Less);
+        assert_eq!(b.cmp_with_commit(&b), Ordering::Equal);
+
+        let mut builds = vec![b, plain, a, b].into_iter().map(CommitOrdered).collect::<Vec<_>>();
+        builds.sort();
+        builds.dedup();
+        assert_eq!(builds, vec![CommitOrdered(plain), CommitOrdered(a), CommitOrdered(b)]);
+    }
+}
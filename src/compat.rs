@@ -0,0 +1,156 @@
+//! EC/SoC compatibility tables, replacing the checks hardcoded in the status server.
+//!
+//! The binary form is a little-endian rule count, then each rule: the 36-byte EC range,
+//! the 36-byte SoC range, and a little-endian flags word with bit 0 set if the pair is
+//! allowed.
+use crate::table::{Record, Table};
+use crate::{Error, SemVer, VersionRange};
+
+const ALLOWED: u32 = 1;
+
+/// The verdict of a compatibility table for one EC/SoC pair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compat {
+    Allowed,
+    Forbidden,
+    /// No rule covers the pair.
+    Unknown,
+}
+
+/// One entry of a `CompatMatrix`: whether EC versions in `ec` work with SoC versions
+/// in `soc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatRule {
+    pub ec: VersionRange,
+    pub soc: VersionRange,
+    pub allowed: bool,
+}
+
+impl CompatRule {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 2 * VersionRange::SERIALIZED_LEN + 4;
+
+    pub const fn allow(ec: VersionRange, soc: VersionRange) -> Self {
+        CompatRule { ec, soc, allowed: true }
+    }
+    pub const fn forbid(ec: VersionRange, soc: VersionRange) -> Self {
+        CompatRule { ec, soc, allowed: false }
+    }
+    fn matches(&self, ec: &SemVer, soc: &SemVer) -> bool {
+        self.ec.contains(ec) && self.soc.contains(soc)
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..CompatRule::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: CompatRule::SERIALIZED_LEN })?;
+        self.ec.write_to(&mut dest[..36])?;
+        self.soc.write_to(&mut dest[36..72])?;
+        dest[72..76].copy_from_slice(&(if self.allowed { ALLOWED } else { 0 }).to_le_bytes());
+        Ok(CompatRule::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<CompatRule, Error> {
+        let (ec, rest) = buf.split_first_chunk::<{ VersionRange::SERIALIZED_LEN }>().ok_or(Error::Truncated)?;
+        let (soc, rest) = rest.split_first_chunk::<{ VersionRange::SERIALIZED_LEN }>().ok_or(Error::Truncated)?;
+        let (flags, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let flags = u32::from_le_bytes(*flags);
+        if flags & !ALLOWED != 0 {
+            return Err(Error::Malformed("unknown compatibility rule flags"));
+        }
+        Ok(CompatRule {
+            ec: VersionRange::read_from(ec)?,
+            soc: VersionRange::read_from(soc)?,
+            allowed: flags & ALLOWED != 0,
+        })
+    }
+}
+
+impl Record for CompatRule {
+    const LEN: usize = CompatRule::SERIALIZED_LEN;
+    fn write_record(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.write_to(buf)
+    }
+    fn read_record(buf: &[u8]) -> Result<Self, Error> {
+        CompatRule::read_from(buf)
+    }
+}
+
+/// An ordered list of rules; the first rule covering a pair decides it. Put narrow
+/// exceptions before the broad rules they carve out of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompatMatrix<'a> {
+    rules: Table<'a, CompatRule>,
+}
+
+impl<'a> CompatMatrix<'a> {
+    pub const fn new(rules: &'a [CompatRule]) -> Self {
+        CompatMatrix { rules: Table::Slice(rules) }
+    }
+    /// Parses a serialized table in place. Every rule is validated up front, so `check`
+    /// cannot fail later.
+    pub fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        Ok(CompatMatrix { rules: Table::read_from(buf)? })
+    }
+    /// Length of the binary form.
+    pub fn serialized_len(&self) -> usize {
+        self.rules.serialized_len()
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.rules.write_to(buf)
+    }
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The rules, in order.
+    pub fn rules(&self) -> impl Iterator<Item = CompatRule> + 'a {
+        self.rules.iter()
+    }
+    /// Looks up whether EC firmware `ec` may run alongside SoC gateware `soc`.
+    pub fn check(&self, ec: &SemVer, soc: &SemVer) -> Compat {
+        match self.rules().find(|rule| rule.matches(ec, soc)) {
+            Some(CompatRule { allowed: true, .. }) => Compat::Allowed,
+            Some(_) => Compat::Forbidden,
+            None => Compat::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_compat_matrix() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let rules = [
+            // a known-bad EC build, carved out of the rule below
+            CompatRule::forbid(VersionRange::inclusive(v("v0.9.9"), v("v0.9.9-99")), VersionRange::half_open(v("v0.9.0"), v("v0.10.0"))),
+            CompatRule::allow(VersionRange::half_open(v("v0.9.0"), v("v0.10.0")), VersionRange::half_open(v("v0.9.0"), v("v0.10.0"))),
+            CompatRule::forbid(VersionRange::half_open(v("v0.9.0"), v("v0.10.0")), VersionRange::half_open(v("v0.10.0"), v("v1.0.0"))),
+        ];
+        let matrix = CompatMatrix::new(&rules);
+        assert_eq!(matrix.check(&v("v0.9.8"), &v("v0.9.12")), Compat::Allowed);
+        assert_eq!(matrix.check(&v("v0.9.9-3"), &v("v0.9.12")), Compat::Forbidden);
+        assert_eq!(matrix.check(&v("v0.9.8"), &v("v0.10.1")), Compat::Forbidden);
+        assert_eq!(matrix.check(&v("v1.0.0"), &v("v0.9.12")), Compat::Unknown);
+
+        let mut buf = [0u8; 4 + 3 * 76];
+        assert_eq!(matrix.serialized_len(), buf.len());
+        assert_eq!(matrix.write_to(&mut buf), Ok(buf.len()));
+        assert_eq!(buf[..4], [3, 0, 0, 0]);
+        let parsed = CompatMatrix::read_from(&buf).unwrap();
+        assert_eq!(parsed.len(), 3);
+        assert!(parsed.rules().eq(rules.iter().copied()));
+        assert_eq!(parsed.check(&v("v0.9.9-3"), &v("v0.9.12")), Compat::Forbidden);
+        assert_eq!(parsed.check(&v("v0.9.8"), &v("v0.9.12")), Compat::Allowed);
+
+        assert_eq!(CompatMatrix::read_from(&buf[..buf.len() - 1]), Err(Error::Truncated));
+        assert_eq!(matrix.write_to(&mut buf[..100]), Err(Error::BufferTooSmall { needed: 232 }));
+        buf[4 + 76 + 72] = 2;
+        assert_eq!(CompatMatrix::read_from(&buf), Err(Error::Malformed("unknown compatibility rule flags")));
+        assert!(CompatMatrix::read_from(&[0; 4]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,118 @@
+//! Integer packings of a `SemVer`.
+//!
+//! The 64-bit form is the layout of the gateware's version CSR. It holds the numeric fields, most significant first:
+//!
+//! ```text
+//!  63      48 47      32 31      16 15       0
+//! +----------+----------+----------+----------+
+//! |   maj    |   min    |   rev    |  extra   |
+//! +----------+----------+----------+----------+
+//! ```
+//!
+//! Because the fields are packed in order of significance, comparing two packed values
+//! as integers gives the same result as comparing the versions. The commit does not fit
+//! and lives in a separate 32-bit register, where 0 means "no commit".
+//!
+//! The 128-bit form is lossless. It places the 64-bit form above the commit and a
+//! presence flag, mirroring the 16-byte serialization:
+//!
+//! ```text
+//!  127                64 63        32 31         0
+//! +--------------------+------------+------------+
+//! |  maj.min.rev.extra |   commit   | has_commit |
+//! +--------------------+------------+------------+
+//! ```
+use crate::SemVer;
+
+impl From<SemVer> for u64 {
+    fn from(ver: SemVer) -> u64 {
+        (ver.maj as u64) << 48
+        | (ver.min as u64) << 32
+        | (ver.rev as u64) << 16
+        | (ver.extra as u64)
+    }
+}
+impl From<&SemVer> for u64 {
+    fn from(ver: &SemVer) -> u64 {
+        (*ver).into()
+    }
+}
+// every bit pattern is a valid version, so this is infallible (and `TryFrom<u64>` comes
+// from the blanket impl); the commit is always `None`.
+impl From<u64> for SemVer {
+    fn from(reg: u64) -> SemVer {
+        SemVer {
+            maj: (reg >> 48) as u16,
+            min: (reg >> 32) as u16,
+            rev: (reg >> 16) as u16,
+            extra: reg as u16,
+            commit: None,
+        }
+    }
+}
+
+impl From<SemVer> for u128 {
+    fn from(ver: SemVer) -> u128 {
+        (u64::from(ver) as u128) << 64
+        | (ver.commit.unwrap_or(0) as u128) << 32
+        | ver.commit.is_some() as u128
+    }
+}
+impl From<&SemVer> for u128 {
+    fn from(ver: &SemVer) -> u128 {
+        (*ver).into()
+    }
+}
+impl From<u128> for SemVer {
+    fn from(packed: u128) -> SemVer {
+        SemVer {
+            commit: if packed as u32 != 0 { Some((packed >> 32) as u32) } else { None },
+            ..SemVer::from((packed >> 64) as u64)
+        }
+    }
+}
+
+impl SemVer {
+    /// Value for the commit register: the commit, or 0 if there is none.
+    pub fn commit_register(&self) -> u32 {
+        self.commit.unwrap_or(0)
+    }
+    /// Rebuilds a version from the 64-bit version register and the commit register. A
+    /// commit register of 0 is read as "no commit".
+    pub fn from_registers(version: u64, commit: u32) -> SemVer {
+        SemVer {
+            commit: if commit != 0 { Some(commit) } else { None },
+            ..SemVer::from(version)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_registers() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(u64::from(ver), 0x0000_0009_0008_02f8);
+        assert_eq!(ver.commit_register(), 0xabcd1234);
+        assert_eq!(SemVer::from(0x0000_0009_0008_02f8u64), SemVer::from_str("v0.9.8-760").unwrap());
+        assert_eq!(SemVer::from(0x0001_0002_0003_0004u64), SemVer::from_str("v1.2.3-4").unwrap());
+        assert_eq!(SemVer::from_registers(u64::from(&ver), ver.commit_register()), ver);
+        assert_eq!(SemVer::from_registers(0x0001_0000_0000_0000, 0), SemVer::from_str("v1.0.0").unwrap());
+        assert!(
+            u64::from(SemVer::from_str("v0.9.8-760").unwrap()) < u64::from(SemVer::from_str("v0.10.0").unwrap())
+        );
+    }
+    #[test]
+    fn test_u128() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(u128::from(ver), 0x0000_0009_0008_02f8_abcd_1234_0000_0001);
+        assert_eq!(SemVer::from(u128::from(ver)), ver);
+        let ver = SemVer::from_str("v0.9.8-760").unwrap();
+        assert_eq!(u128::from(&ver), 0x0000_0009_0008_02f8_0000_0000_0000_0000);
+        assert_eq!(SemVer::from(u128::from(ver)), ver);
+        // a zero commit is still distinguishable from no commit
+        let ver = SemVer::from_str("v0.9.8-760-g0").unwrap();
+        assert_eq!(SemVer::from(u128::from(ver)), ver);
+    }
+}
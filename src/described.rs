@@ -0,0 +1,80 @@
+//! A version together with the width `git describe` abbreviated its commit to, for
+//! tools that need to print the description back exactly as git wrote it.
+use core::fmt;
+use crate::{CommitStr, ParseError, SemVer};
+
+/// A `SemVer` and the number of hex digits its commit was written with, so that
+/// `v0.9.8-760-g0abc123` displays with its leading zero rather than as `gabc123`.
+///
+/// The width is text-only: the 16-byte form, TLV, serde and the FFI carry just the
+/// `SemVer`, and a version read back from any of them has a width of 0.
+///
+/// ```
+/// # use xous_semver::DescribedVersion;
+/// let described: DescribedVersion = "v0.9.8-760-g0abc123".parse().unwrap();
+/// assert_eq!(described.to_string(), "v0.9.8-760-g0abc123");
+/// assert_eq!(described.version.to_string(), "v0.9.8-760-gabc123");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DescribedVersion {
+    pub version: SemVer,
+    /// Hex digits the commit was written with; 0 prints it without leading zeros.
+    pub commit_digits: u8,
+}
+
+impl DescribedVersion {
+    pub const fn new(version: SemVer, commit_digits: u8) -> DescribedVersion {
+        DescribedVersion { version, commit_digits }
+    }
+    /// The commit in lowercase hex, padded to the width it was written with.
+    pub fn commit_str(&self) -> Option<CommitStr> {
+        Some(CommitStr::new(self.version.commit?, self.commit_digits))
+    }
+}
+
+impl From<SemVer> for DescribedVersion {
+    fn from(version: SemVer) -> DescribedVersion {
+        DescribedVersion::new(version, 0)
+    }
+}
+impl From<DescribedVersion> for SemVer {
+    fn from(described: DescribedVersion) -> SemVer {
+        described.version
+    }
+}
+
+/// The `SemVer` text form with the commit at its original width, including the
+/// alternate `{:#}` that leaves out a zero `extra`.
+impl fmt::Display for DescribedVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.version.display_pieces_padded(f.alternate(), self.commit_digits, |piece| f.write_str(piece))
+    }
+}
+/// Accepts the same forms as `SemVer::from_str`, remembering the commit's width.
+impl core::str::FromStr for DescribedVersion {
+    type Err = ParseError;
+    fn from_str(revstr: &str) -> Result<Self, ParseError> {
+        let ([maj, min, rev, extra], commit, commit_digits) = crate::parse_components::<u16>(revstr)?;
+        Ok(DescribedVersion::new(SemVer { maj, min, rev, extra, commit }, commit_digits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_described_version() {
+        let described: DescribedVersion = "v0.9.8-760-g0abc123".parse().unwrap();
+        assert_eq!(described.version, SemVer::from_str("v0.9.8-760-gabc123").unwrap());
+        assert_eq!(described.commit_digits, 7);
+        assert_eq!(described.commit_str().as_deref(), Some("0abc123"));
+        assert_eq!(described.to_string(), "v0.9.8-760-g0abc123");
+        assert_eq!(described.to_string().parse(), Ok(described));
+        assert_eq!("v1.2.3-0-g00000000".parse::<DescribedVersion>().map(|d| format!("{:#}", d)), Ok("v1.2.3-g00000000".to_string()));
+        assert_eq!("v1.2.3".parse::<DescribedVersion>().map(|d| d.to_string()), Ok("v1.2.3-0".to_string()));
+        assert_eq!("1.2.3".parse::<DescribedVersion>(), Err(ParseError::MissingPrefix));
+        // the binary form drops the width
+        let unpacked = DescribedVersion::from(SemVer::from(described.version.to_le_bytes()));
+        assert_eq!(unpacked.to_string(), "v0.9.8-760-gabc123");
+    }
+}
@@ -0,0 +1,103 @@
+//! The DFU file suffix, so `dfu-util` update flows carry and check the version.
+//!
+//! The suffix is the last 16 bytes of a DFU file, all little-endian: `bcdDevice`,
+//! `idProduct`, `idVendor`, `bcdDFU` (0x0100), the signature `UFD`, `bLength` (16), and
+//! a CRC-32 over the rest of the file. The CRC is the raw register, without the final
+//! inversion, as `dfu-util` computes it.
+use crate::crc::crc32_update;
+use crate::{Error, SemVer};
+
+const BCD_DFU: u16 = 0x0100;
+const SIGNATURE: [u8; 3] = *b"UFD";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DfuSuffix {
+    pub bcd_device: u16,
+    pub id_product: u16,
+    pub id_vendor: u16,
+}
+
+impl DfuSuffix {
+    /// Length of the suffix.
+    pub const LEN: usize = 16;
+
+    /// A suffix for firmware `ver`, with `bcdDevice` from `SemVer::to_bcd_device`.
+    pub fn for_version(ver: &SemVer, id_vendor: u16, id_product: u16) -> Self {
+        DfuSuffix { bcd_device: ver.to_bcd_device(), id_product, id_vendor }
+    }
+    /// The version encoded in `bcdDevice`, as `JJ.M.N` with `extra` 0 and no commit.
+    pub fn version(&self) -> Result<SemVer, Error> {
+        SemVer::from_bcd_device(self.bcd_device)
+    }
+    /// Appends the suffix to the firmware in `file[..payload_len]`, returning the length
+    /// of the complete DFU file.
+    pub fn append(&self, file: &mut [u8], payload_len: usize) -> Result<usize, Error> {
+        let needed = payload_len + DfuSuffix::LEN;
+        let file = file.get_mut(..needed).ok_or(Error::BufferTooSmall { needed })?;
+        let suffix = &mut file[payload_len..];
+        suffix[0..2].copy_from_slice(&self.bcd_device.to_le_bytes());
+        suffix[2..4].copy_from_slice(&self.id_product.to_le_bytes());
+        suffix[4..6].copy_from_slice(&self.id_vendor.to_le_bytes());
+        suffix[6..8].copy_from_slice(&BCD_DFU.to_le_bytes());
+        suffix[8..11].copy_from_slice(&SIGNATURE);
+        suffix[11] = DfuSuffix::LEN as u8;
+        let crc = crc32_update(0xffff_ffff, &file[..needed - 4]);
+        file[needed - 4..].copy_from_slice(&crc.to_le_bytes());
+        Ok(needed)
+    }
+    /// Reads and checks the suffix at the end of a DFU file, returning it with the
+    /// firmware it covers. A CRC mismatch is reported as `Error::Corrupt`.
+    pub fn read_from(file: &[u8]) -> Result<(DfuSuffix, &[u8]), Error> {
+        let (payload, suffix) = file.split_last_chunk::<16>().ok_or(Error::Truncated)?;
+        let [d0, d1, p0, p1, v0, v1, b0, b1, s0, s1, s2, len, c0, c1, c2, c3] = *suffix;
+        if [s0, s1, s2] != SIGNATURE {
+            return Err(Error::Malformed("missing DFU suffix signature"));
+        }
+        if len as usize != DfuSuffix::LEN {
+            return Err(Error::Malformed("unsupported DFU suffix length"));
+        }
+        if u16::from_le_bytes([b0, b1]) != BCD_DFU {
+            return Err(Error::Malformed("unsupported bcdDFU"));
+        }
+        let (covered, _) = file.split_last_chunk::<4>().ok_or(Error::Truncated)?;
+        if crc32_update(0xffff_ffff, covered) != u32::from_le_bytes([c0, c1, c2, c3]) {
+            return Err(Error::Corrupt);
+        }
+        let dfu = DfuSuffix {
+            bcd_device: u16::from_le_bytes([d0, d1]),
+            id_product: u16::from_le_bytes([p0, p1]),
+            id_vendor: u16::from_le_bytes([v0, v1]),
+        };
+        Ok((dfu, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_dfu_suffix() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let dfu = DfuSuffix::for_version(&ver, 0x1209, 0x3613);
+        let mut file = [0u8; 64];
+        file[..32].fill(0x5a);
+        assert_eq!(dfu.append(&mut file, 32), Ok(48));
+        assert_eq!(file[32..44], [0x98, 0x00, 0x13, 0x36, 0x09, 0x12, 0x00, 0x01, b'U', b'F', b'D', 16]);
+        let (read, payload) = DfuSuffix::read_from(&file[..48]).unwrap();
+        assert_eq!(read, dfu);
+        assert_eq!(payload, &[0x5a; 32]);
+        assert_eq!(read.version(), Ok(SemVer::new(0, 9, 8)));
+
+        // the stored CRC is the standard CRC-32 without its final inversion
+        let mut empty = [0u8; 16];
+        DfuSuffix { bcd_device: 0xffff, id_product: 0xffff, id_vendor: 0xffff }.append(&mut empty, 0).unwrap();
+        assert_eq!(u32::from_le_bytes(empty[12..].try_into().unwrap()), !crate::crc::crc32(&empty[..12]));
+
+        let mut corrupted = file;
+        corrupted[0] ^= 1;
+        assert_eq!(DfuSuffix::read_from(&corrupted[..48]), Err(Error::Corrupt));
+        assert_eq!(DfuSuffix::read_from(&file[..47]), Err(Error::Malformed("missing DFU suffix signature")));
+        assert_eq!(DfuSuffix::read_from(&file[..15]), Err(Error::Truncated));
+        assert_eq!(dfu.append(&mut file, 49), Err(Error::BufferTooSmall { needed: 65 }));
+    }
+}
@@ -0,0 +1,227 @@
+//! Formatting without `core::fmt`, for panic handlers and pre-heap boot code where the
+//! fmt machinery is unavailable or too big. The output is identical to `Display`.
+use crate::{Error, SemVer};
+
+/// The longest `Display` output: `v65535.65535.65535-65535-gffffffff`.
+pub const MAX_DISPLAY_LEN: usize = 34;
+
+// The helpers below are structurally panic-free, with no indexing that could go out of
+// bounds and no unwraps, so panic handlers and the loader can rely on them.
+
+// returns the digits of `bytes` from the first nonzero one, keeping at least the last
+fn trim_zeros(digits: &[u8]) -> &str {
+    let start = digits.iter().position(|&d| d != b'0').unwrap_or(digits.len().saturating_sub(1));
+    core::str::from_utf8(digits.get(start..).unwrap_or(&[])).unwrap_or("")
+}
+
+// decimal without leading zeros, like `{}`
+fn decimal(mut v: u16, buf: &mut [u8; 5]) -> &str {
+    for digit in buf.iter_mut().rev() {
+        *digit = b'0' + (v % 10) as u8;
+        v /= 10;
+    }
+    trim_zeros(buf)
+}
+
+// lowercase hex without leading zeros, like `{:x}`
+fn hex(v: u32, buf: &mut [u8; 8]) -> &str {
+    const DIGITS: [u8; 16] = *b"0123456789abcdef";
+    for (i, digit) in buf.iter_mut().enumerate() {
+        *digit = DIGITS[(v >> (28 - 4 * (i as u32 & 7))) as usize & 0xf];
+    }
+    trim_zeros(buf)
+}
+
+// lowercase hex padded to at least `width` digits, like `{:0width$x}` capped at 8
+fn hex_padded(v: u32, width: u8, buf: &mut [u8; 8]) -> &str {
+    let len = hex(v, buf).len().max(usize::from(width.min(8)));
+    core::str::from_utf8(buf.get(8 - len..).unwrap_or(&[])).unwrap_or("")
+}
+
+/// The commit hash in hex, from `commit_str`, held on the stack.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct CommitStr {
+    buf: [u8; 8],
+    start: u8,
+}
+impl CommitStr {
+    // `commit` in hex, padded to `digits`
+    pub(crate) fn new(commit: u32, digits: u8) -> CommitStr {
+        let mut buf = [0u8; 8];
+        let start = 8 - hex_padded(commit, digits, &mut buf).len() as u8;
+        CommitStr { buf, start }
+    }
+    pub fn as_str(&self) -> &str {
+        core::str::from_utf8(self.buf.get(usize::from(self.start)..).unwrap_or(&[])).unwrap_or("")
+    }
+}
+impl core::ops::Deref for CommitStr {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl AsRef<str> for CommitStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl core::fmt::Debug for CommitStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl core::fmt::Display for CommitStr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SemVer {
+    /// The commit in lowercase hex without leading zeros, as `Display` writes it. Use
+    /// `DescribedVersion` to keep the width `git describe` printed it with.
+    pub fn commit_str(&self) -> Option<CommitStr> {
+        Some(CommitStr::new(self.commit?, 0))
+    }
+    /// Calls `f` with each piece of the `Display` output in turn. `Display` itself goes
+    /// through here, so every text form shares one panic-free path.
+    pub(crate) fn display_pieces<E>(&self, f: impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        self.display_pieces_with(false, f)
+    }
+    /// As `display_pieces`, but with `short` set, a zero `extra` is left out, as in the
+    /// alternate `{:#}` form.
+    pub(crate) fn display_pieces_with<E>(&self, short: bool, f: impl FnMut(&str) -> Result<(), E>) -> Result<(), E> {
+        self.display_pieces_padded(short, 0, f)
+    }
+    /// As `display_pieces_with`, with the commit zero-padded to `commit_digits`.
+    pub(crate) fn display_pieces_padded<E>(
+        &self,
+        short: bool,
+        commit_digits: u8,
+        mut f: impl FnMut(&str) -> Result<(), E>,
+    ) -> Result<(), E> {
+        let mut digits = [0u8; 5];
+        f("v")?;
+        f(decimal(self.maj, &mut digits))?;
+        f(".")?;
+        f(decimal(self.min, &mut digits))?;
+        f(".")?;
+        f(decimal(self.rev, &mut digits))?;
+        if !(short && self.extra == 0) {
+            f("-")?;
+            f(decimal(self.extra, &mut digits))?;
+        }
+        if let Some(commit) = self.commit {
+            let mut hex_digits = [0u8; 8];
+            f("-g")?;
+            f(hex_padded(commit, commit_digits, &mut hex_digits))?;
+        }
+        Ok(())
+    }
+    /// Length of the `Display` output, at most `MAX_DISPLAY_LEN`.
+    pub fn display_len(&self) -> usize {
+        let mut len = 0;
+        let _ = self.display_pieces::<()>(|piece| {
+            len += piece.len();
+            Ok(())
+        });
+        len
+    }
+    /// Writes the `Display` output to the start of `buf` without going through
+    /// `core::fmt`, returning the number of bytes written. Nothing is written if `buf`
+    /// is too small.
+    pub fn write_display(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.display_len();
+        let mut out = buf.get_mut(..needed).ok_or(Error::BufferTooSmall { needed })?.iter_mut();
+        let _ = self.display_pieces::<()>(|piece| {
+            // the piece goes first, so `out` isn't advanced past its end
+            for (byte, dest) in piece.bytes().zip(&mut out) {
+                *dest = byte;
+            }
+            Ok(())
+        });
+        Ok(needed)
+    }
+}
+
+/// The `Display` output held on the stack, from `SemVer::to_display_buf`. Derefs to
+/// `&str`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct DisplayBuf {
+    buf: [u8; MAX_DISPLAY_LEN],
+    len: u8,
+}
+
+impl DisplayBuf {
+    pub fn as_str(&self) -> &str {
+        // only ever filled from ASCII pieces
+        core::str::from_utf8(self.buf.get(..usize::from(self.len)).unwrap_or(&[])).unwrap_or("")
+    }
+}
+
+impl core::ops::Deref for DisplayBuf {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl AsRef<str> for DisplayBuf {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl core::fmt::Debug for DisplayBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+impl core::fmt::Display for DisplayBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl SemVer {
+    /// The `Display` output as an owned, stack-allocated string.
+    pub fn to_display_buf(&self) -> DisplayBuf {
+        let mut buf = [0u8; MAX_DISPLAY_LEN];
+        // the buffer holds the longest output, so this cannot fail
+        let len = self.write_display(&mut buf).unwrap_or(0);
+        DisplayBuf { buf, len: len as u8 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_write_display() {
+        let mut buf = [0u8; MAX_DISPLAY_LEN];
+        for s in ["v0.9.8-760-gabcd1234", "v0.9.8-760", "v0.0.0-0", "v65535.65535.65535-65535-gffffffff", "v1.2.3-0-g0"] {
+            let ver = SemVer::from_str(s).unwrap();
+            assert_eq!(ver.display_len(), s.len());
+            assert_eq!(ver.write_display(&mut buf), Ok(s.len()));
+            assert_eq!(&buf[..s.len()], s.as_bytes());
+        }
+        let ver = SemVer::from_str("v0.9.8-760").unwrap();
+        assert_eq!(ver.write_display(&mut buf[..9]), Err(Error::BufferTooSmall { needed: 10 }));
+    }
+    #[test]
+    fn test_display_buf() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let shown = ver.to_display_buf();
+        assert_eq!(&*shown, "v0.9.8-760-gabcd1234");
+        assert!(shown.starts_with("v0.9"));
+        assert_eq!(format!("{}", shown), ver.to_string());
+        assert_eq!(format!("{:?}", shown), "\"v0.9.8-760-gabcd1234\"");
+        let widest = SemVer::from_str("v65535.65535.65535-65535-gffffffff").unwrap();
+        assert_eq!(widest.to_display_buf().len(), MAX_DISPLAY_LEN);
+    }
+    #[test]
+    fn test_commit_str() {
+        let ver = SemVer::from_str("v0.9.8-760-g0abc123").unwrap();
+        assert_eq!(ver.commit_str().unwrap().as_str(), "abc123");
+        assert_eq!(ver.to_string(), "v0.9.8-760-gabc123");
+        assert_eq!(SemVer::new(1, 2, 3).commit_str(), None);
+    }
+}
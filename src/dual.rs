@@ -0,0 +1,107 @@
+//! A redundant flash record holding two CRC-protected copies of the version, so a
+//! power cut while stamping a new version leaves the previous one readable.
+//!
+//! Each copy is a little-endian sequence number, the 16-byte serialization, and a
+//! little-endian CRC-32 over the preceding 20 bytes, for 24 bytes per copy and 48 in
+//! total. Writes go to the copy not currently in use, with the next sequence number.
+use crate::crc::crc32;
+use crate::{Error, SemVer};
+
+const COPY_LEN: usize = 24;
+
+// reads one copy, returning its sequence number and version
+fn read_copy(copy: &[u8]) -> Result<(u32, SemVer), Error> {
+    let (body, rest) = copy.split_first_chunk::<20>().ok_or(Error::Truncated)?;
+    let (crc, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+    if crc32(body) != u32::from_le_bytes(*crc) {
+        return Err(Error::Corrupt);
+    }
+    let (seq, ver) = body.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+    let ver = ver.first_chunk::<16>().ok_or(Error::Truncated)?;
+    Ok((u32::from_le_bytes(*seq), SemVer::from_bytes_strict(ver)?))
+}
+
+// true if sequence number `a` is newer than `b`, allowing for wraparound
+fn newer(a: u32, b: u32) -> bool {
+    (a.wrapping_sub(b) as i32) > 0
+}
+
+// the index and contents of the copy to trust, if either is valid
+fn best(record: &[u8]) -> Result<(usize, u32, SemVer), Error> {
+    match (read_copy(&record[..COPY_LEN]), read_copy(&record[COPY_LEN..])) {
+        (Ok((a_seq, a)), Ok((b_seq, b))) => Ok(if newer(b_seq, a_seq) { (1, b_seq, b) } else { (0, a_seq, a) }),
+        (Ok((seq, ver)), Err(_)) => Ok((0, seq, ver)),
+        (Err(_), Ok((seq, ver))) => Ok((1, seq, ver)),
+        (Err(e), Err(_)) => Err(e),
+    }
+}
+
+impl SemVer {
+    /// Length of the dual-copy record.
+    pub const DUAL_LEN: usize = 2 * COPY_LEN;
+
+    /// Reads the newest valid copy of a dual-copy record. If neither copy is valid, the
+    /// first copy's error is returned; erased flash reads as `Error::Corrupt`.
+    pub fn read_best(buf: &[u8]) -> Result<SemVer, Error> {
+        let record = buf.get(..SemVer::DUAL_LEN).ok_or(Error::Truncated)?;
+        best(record).map(|(_, _, ver)| ver)
+    }
+    /// Stamps `self` into the dual-copy record at the start of `buf`, which must hold
+    /// the record's current contents. Only the copy not currently in use is rewritten;
+    /// its byte offset is returned, so callers need program only those 24 bytes. A
+    /// record with no valid copy, such as erased flash, starts at copy 0.
+    pub fn write_dual(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let record = buf.get_mut(..SemVer::DUAL_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SemVer::DUAL_LEN })?;
+        let (offset, seq) = match best(record) {
+            Ok((index, seq, _)) => ((1 - index) * COPY_LEN, seq.wrapping_add(1)),
+            Err(_) => (0, 0),
+        };
+        let copy = &mut record[offset..offset + COPY_LEN];
+        copy[..4].copy_from_slice(&seq.to_le_bytes());
+        copy[4..20].copy_from_slice(&<[u8; 16]>::from(self));
+        let crc = crc32(&copy[..20]);
+        copy[20..24].copy_from_slice(&crc.to_le_bytes());
+        Ok(offset)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_dual_record() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let mut flash = [0xffu8; 48];
+        assert_eq!(SemVer::read_best(&flash), Err(Error::Corrupt));
+        assert_eq!(v("v0.9.8").write_dual(&mut flash), Ok(0));
+        assert_eq!(SemVer::read_best(&flash), Ok(v("v0.9.8")));
+        assert_eq!(v("v0.9.9").write_dual(&mut flash), Ok(24));
+        assert_eq!(SemVer::read_best(&flash), Ok(v("v0.9.9")));
+        assert_eq!(v("v0.9.10").write_dual(&mut flash), Ok(0));
+        assert_eq!(flash[..4], [2, 0, 0, 0]);
+        assert_eq!(SemVer::read_best(&flash), Ok(v("v0.9.10")));
+
+        // a power cut halfway through the next stamp leaves the previous version
+        let mut torn = flash;
+        v("v0.9.11").write_dual(&mut torn).unwrap();
+        torn[24 + 12..48].fill(0xff);
+        assert_eq!(SemVer::read_best(&torn), Ok(v("v0.9.10")));
+        // and the retry goes back to the torn copy
+        assert_eq!(v("v0.9.11").write_dual(&mut torn), Ok(24));
+        assert_eq!(SemVer::read_best(&torn), Ok(v("v0.9.11")));
+
+        // sequence numbers wrap
+        let mut wrapped = [0u8; 48];
+        v("v1.0.0").write_dual(&mut wrapped).unwrap();
+        wrapped[..4].copy_from_slice(&u32::MAX.to_le_bytes());
+        let crc = crc32(&wrapped[..20]);
+        wrapped[20..24].copy_from_slice(&crc.to_le_bytes());
+        assert_eq!(v("v1.0.1").write_dual(&mut wrapped), Ok(24));
+        assert_eq!(wrapped[24..28], [0, 0, 0, 0]);
+        assert_eq!(SemVer::read_best(&wrapped), Ok(v("v1.0.1")));
+
+        assert_eq!(SemVer::read_best(&flash[..47]), Err(Error::Truncated));
+        assert_eq!(v("v1.0.0").write_dual(&mut flash[..47]), Err(Error::BufferTooSmall { needed: 48 }));
+    }
+}
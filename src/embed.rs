@@ -0,0 +1,134 @@
+//! Placing the version in a named link section, where host tools can find and rewrite
+//! or verify it in the ELF or the raw binary, and finding it again in an arbitrary
+//! firmware image without parsing the image format.
+use crate::{Error, SemVer};
+
+/// Prefix of an embedded version record, chosen to be unlikely to occur by accident.
+pub const EMBED_MAGIC: [u8; 8] = *b"XSEMVER\x01";
+/// Size of an embedded record: the magic, then the 16-byte serialization.
+pub const EMBED_LEN: usize = EMBED_MAGIC.len() + SemVer::SERIALIZED_LEN;
+
+/// Defines a `pub static $name: [u8; EMBED_LEN]` holding `EMBED_MAGIC` and the serialized
+/// `$version`, placed in the link section `.xous.version` (or the given one) and kept
+/// even if nothing references it.
+///
+/// ```
+/// use xous_semver::{embed_version, SemVer};
+/// embed_version!(VERSION_RECORD, SemVer::new(0, 9, 8));
+/// ```
+///
+/// Mach-O requires section names of the form `"__DATA,__xous_version"`.
+#[macro_export]
+macro_rules! embed_version {
+    ($name:ident, $version:expr) => {
+        $crate::embed_version!($name, $version, ".xous.version");
+    };
+    ($name:ident, $version:expr, $section:literal) => {
+        #[used]
+        #[link_section = $section]
+        pub static $name: [u8; $crate::EMBED_LEN] = $crate::__embed_record(&$version);
+    };
+}
+
+#[doc(hidden)]
+pub const fn __embed_record(version: &SemVer) -> [u8; EMBED_LEN] {
+    let mut record = [0u8; EMBED_LEN];
+    let mut i = 0;
+    while i < EMBED_MAGIC.len() {
+        record[i] = EMBED_MAGIC[i];
+        i += 1;
+    }
+    let ser = version.to_le_bytes();
+    let mut j = 0;
+    while j < ser.len() {
+        record[i + j] = ser[j];
+        j += 1;
+    }
+    record
+}
+
+impl SemVer {
+    /// The embedded record: `EMBED_MAGIC` followed by the 16-byte serialization.
+    pub fn to_embedded(&self) -> [u8; EMBED_LEN] {
+        __embed_record(self)
+    }
+    /// Writes the embedded record to the start of `buf`, returning the number of bytes
+    /// written.
+    pub fn write_embedded(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..EMBED_LEN).ok_or(Error::BufferTooSmall { needed: EMBED_LEN })?;
+        dest.copy_from_slice(&self.to_embedded());
+        Ok(EMBED_LEN)
+    }
+}
+
+/// The first embedded version record in `image` and the offset of its magic. Magic
+/// matches whose record is invalid (e.g. the magic constant itself in a string table)
+/// are skipped.
+pub fn find_embedded(image: &[u8]) -> Option<(usize, SemVer)> {
+    embedded_versions(image).next()
+}
+
+/// Every valid embedded version record in `image`, with the offsets of their magic.
+pub fn embedded_versions(image: &[u8]) -> EmbeddedVersions<'_> {
+    EmbeddedVersions { image, pos: 0 }
+}
+
+pub struct EmbeddedVersions<'a> {
+    image: &'a [u8],
+    pos: usize,
+}
+
+impl Iterator for EmbeddedVersions<'_> {
+    type Item = (usize, SemVer);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos + EMBED_LEN <= self.image.len() {
+            let at = self.pos;
+            self.pos += 1;
+            let Some((magic, rest)) = self.image.get(at..).and_then(|rest| rest.split_first_chunk::<8>()) else {
+                break;
+            };
+            if *magic != EMBED_MAGIC {
+                continue;
+            }
+            let Some(record) = rest.first_chunk::<16>() else {
+                break;
+            };
+            if let Ok(ver) = SemVer::from_bytes_strict(record) {
+                self.pos = at + EMBED_LEN;
+                return Some((at, ver));
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    embed_version!(TEST_RECORD, SemVer { extra: 760, commit: Some(0xabcd1234), ..SemVer::new(0, 9, 8) });
+    #[test]
+    fn test_embed() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(TEST_RECORD[..8], EMBED_MAGIC);
+        assert_eq!(TEST_RECORD[8..], <[u8; 16]>::from(ver));
+        assert_eq!(__embed_record(&SemVer::new(1, 2, 3))[8..], <[u8; 16]>::from(SemVer::new(1, 2, 3)));
+    }
+    #[test]
+    fn test_find_embedded() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let mut image = [0x5au8; 200];
+        // a bare magic with garbage after it, as in a string table
+        image[10..18].copy_from_slice(&EMBED_MAGIC);
+        image[26..42].fill(0xff);
+        assert_eq!(ver.write_embedded(&mut image[101..]), Ok(EMBED_LEN));
+        assert_eq!(SemVer::new(1, 0, 0).write_embedded(&mut image[176..]), Ok(EMBED_LEN));
+        assert_eq!(find_embedded(&image), Some((101, ver)));
+        let all: Vec<_> = embedded_versions(&image).collect();
+        assert_eq!(all, vec![(101, ver), (176, SemVer::new(1, 0, 0))]);
+        assert_eq!(find_embedded(&image[..199]), Some((101, ver)));
+        assert_eq!(embedded_versions(&image[..199]).count(), 1);
+        assert_eq!(find_embedded(&[]), None);
+        assert_eq!(ver.write_embedded(&mut image[..23]), Err(Error::BufferTooSmall { needed: 24 }));
+        assert_eq!(ver.to_embedded(), TEST_RECORD);
+    }
+}
@@ -0,0 +1,60 @@
+use core::fmt;
+
+/// Errors produced by the binary encodings of a `SemVer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The destination buffer cannot hold the encoding; `needed` bytes are required.
+    BufferTooSmall { needed: usize },
+    /// The source data ends in the middle of a record.
+    Truncated,
+    /// The data failed its integrity check.
+    Corrupt,
+    /// The source data is structurally invalid.
+    Malformed(&'static str),
+    /// The named component does not fit in the target representation.
+    Overflow(&'static str),
+    /// The data carries a field, identified by its tag, that this version of the crate
+    /// does not understand.
+    UnknownField(u8),
+    /// A signature did not verify against the expected key.
+    BadSignature,
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::BufferTooSmall { needed } => write!(f, "buffer too small, {} bytes needed", needed),
+            Error::Truncated => write!(f, "data is truncated"),
+            Error::Corrupt => write!(f, "checksum mismatch"),
+            Error::Malformed(why) => write!(f, "malformed data: {}", why),
+            Error::Overflow(component) => write!(f, "{} is out of range", component),
+            Error::UnknownField(tag) => write!(f, "unknown field with tag {:#04x}", tag),
+            Error::BadSignature => write!(f, "signature verification failed"),
+        }
+    }
+}
+impl core::error::Error for Error {}
+
+/// Errors from parsing the `vX.Y.Z[-extra][-gcommit]` string form.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// There was nothing to parse.
+    Empty,
+    /// The string does not start with `v` (`hw` for a `HwRev`).
+    MissingPrefix,
+    /// The string does not have between 3 and 5 fields.
+    FieldCount,
+    /// The named component (`maj`, `min`, `rev`, `extra` or `commit`, or a `HwRev`'s
+    /// `board`, `variant` or `strap`) is not a valid number.
+    Component(&'static str),
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "no version found"),
+            ParseError::MissingPrefix => write!(f, "semver does not start with 'v'!"),
+            ParseError::FieldCount => write!(f, "semver string has wrong number of fields"),
+            ParseError::Component(component) => write!(f, "error parsing {}", component),
+        }
+    }
+}
+impl core::error::Error for ParseError {}
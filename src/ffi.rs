@@ -0,0 +1,161 @@
+//! C bindings, so the C bootloader stub and factory-test fixtures share this crate's
+//! parsing, formatting and ordering. See `include/xous_semver.h` for the C declarations.
+//!
+//! Functions returning `i32` use 0 for success and a negative value for failure.
+use core::ffi::{c_char, CStr};
+use core::fmt::Write;
+use crate::SemVer;
+
+pub const XOUS_SEMVER_OK: i32 = 0;
+pub const XOUS_SEMVER_ERR_NULL: i32 = -1;
+pub const XOUS_SEMVER_ERR_PARSE: i32 = -2;
+pub const XOUS_SEMVER_ERR_BUFFER: i32 = -3;
+
+/// C view of a version. The field order and widths match the 16-byte serialization.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct XousSemVer {
+    pub maj: u16,
+    pub min: u16,
+    pub rev: u16,
+    pub extra: u16,
+    pub commit: u32,
+    pub has_commit: u32,
+}
+impl From<SemVer> for XousSemVer {
+    fn from(ver: SemVer) -> Self {
+        XousSemVer {
+            maj: ver.maj,
+            min: ver.min,
+            rev: ver.rev,
+            extra: ver.extra,
+            commit: ver.commit.unwrap_or(0),
+            has_commit: ver.commit.is_some() as u32,
+        }
+    }
+}
+impl From<XousSemVer> for SemVer {
+    fn from(ver: XousSemVer) -> Self {
+        SemVer {
+            maj: ver.maj,
+            min: ver.min,
+            rev: ver.rev,
+            extra: ver.extra,
+            commit: if ver.has_commit != 0 { Some(ver.commit) } else { None },
+        }
+    }
+}
+
+/// Parses a NUL-terminated version string into `*out`.
+///
+/// # Safety
+/// `s` must be NULL or a valid NUL-terminated string, and `out` must be NULL or valid
+/// for writes.
+#[no_mangle]
+pub unsafe extern "C" fn xous_semver_parse(s: *const c_char, out: *mut XousSemVer) -> i32 {
+    if s.is_null() || out.is_null() {
+        return XOUS_SEMVER_ERR_NULL;
+    }
+    let parsed = CStr::from_ptr(s).to_str().ok().and_then(|s| SemVer::from_str(s).ok());
+    match parsed {
+        Some(ver) => {
+            *out = ver.into();
+            XOUS_SEMVER_OK
+        }
+        None => XOUS_SEMVER_ERR_PARSE,
+    }
+}
+
+/// Formats `*ver` into `buf` as a NUL-terminated string. Returns the string length
+/// (excluding the NUL), or `XOUS_SEMVER_ERR_BUFFER` if `len` bytes are not enough.
+///
+/// # Safety
+/// `ver` must be NULL or valid for reads, and `buf` must be NULL or valid for `len`
+/// bytes of writes.
+#[no_mangle]
+pub unsafe extern "C" fn xous_semver_format(ver: *const XousSemVer, buf: *mut c_char, len: usize) -> i32 {
+    if ver.is_null() || buf.is_null() {
+        return XOUS_SEMVER_ERR_NULL;
+    }
+    let dest = core::slice::from_raw_parts_mut(buf as *mut u8, len);
+    let mut w = SliceWriter { buf: dest, pos: 0 };
+    // leave room for the terminator
+    if write!(w, "{}", SemVer::from(*ver)).is_err() || w.pos >= len {
+        return XOUS_SEMVER_ERR_BUFFER;
+    }
+    let end = w.pos;
+    w.buf[end] = 0;
+    end as i32
+}
+
+/// Compares two versions, returning -1, 0 or 1. Like `Ord` for `SemVer`, the commit is
+/// not considered. NULL sorts before any version.
+///
+/// # Safety
+/// `a` and `b` must each be NULL or valid for reads.
+#[no_mangle]
+pub unsafe extern "C" fn xous_semver_cmp(a: *const XousSemVer, b: *const XousSemVer) -> i32 {
+    let a = a.as_ref().map(|v| SemVer::from(*v));
+    let b = b.as_ref().map(|v| SemVer::from(*v));
+    a.cmp(&b) as i32
+}
+
+/// Writes the 16-byte serialization of `*ver` to `out`.
+///
+/// # Safety
+/// `ver` must be NULL or valid for reads, and `out` must be NULL or valid for 16 bytes
+/// of writes.
+#[no_mangle]
+pub unsafe extern "C" fn xous_semver_to_bytes(ver: *const XousSemVer, out: *mut u8) -> i32 {
+    if ver.is_null() || out.is_null() {
+        return XOUS_SEMVER_ERR_NULL;
+    }
+    let bytes: [u8; 16] = SemVer::from(*ver).into();
+    core::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    XOUS_SEMVER_OK
+}
+
+struct SliceWriter<'a> {
+    buf: &'a mut [u8],
+    pos: usize,
+}
+impl Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let dest = self.buf.get_mut(self.pos..self.pos + s.len()).ok_or(core::fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.pos += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_ffi() {
+        let mut ver = XousSemVer::default();
+        unsafe {
+            assert_eq!(xous_semver_parse(c"v0.9.8-760-gabcd1234".as_ptr(), &mut ver), XOUS_SEMVER_OK);
+            assert_eq!(SemVer::from(ver), SemVer::from_str("v0.9.8-760-gabcd1234").unwrap());
+            assert_eq!(xous_semver_parse(c"0.9.8".as_ptr(), &mut ver), XOUS_SEMVER_ERR_PARSE);
+            assert_eq!(xous_semver_parse(core::ptr::null(), &mut ver), XOUS_SEMVER_ERR_NULL);
+
+            let mut buf = [0x55 as c_char; 32];
+            assert_eq!(xous_semver_format(&ver, buf.as_mut_ptr(), buf.len()), 20);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), "v0.9.8-760-gabcd1234");
+            // no room for the terminator
+            assert_eq!(xous_semver_format(&ver, buf.as_mut_ptr(), 20), XOUS_SEMVER_ERR_BUFFER);
+            assert_eq!(xous_semver_format(&ver, buf.as_mut_ptr(), 4), XOUS_SEMVER_ERR_BUFFER);
+
+            let newer = XousSemVer::from(SemVer::new(1, 0, 0));
+            assert_eq!(xous_semver_cmp(&ver, &newer), -1);
+            assert_eq!(xous_semver_cmp(&newer, &ver), 1);
+            assert_eq!(xous_semver_cmp(&ver, &ver), 0);
+            assert_eq!(xous_semver_cmp(core::ptr::null(), &ver), -1);
+
+            let mut bytes = [0u8; 16];
+            assert_eq!(xous_semver_to_bytes(&ver, bytes.as_mut_ptr()), XOUS_SEMVER_OK);
+            assert_eq!(bytes, <[u8; 16]>::from(SemVer::from(ver)));
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! A stable 64-bit fingerprint of a version, for telemetry that must compare across
+//! crate releases and hosts.
+use crate::SemVer;
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl SemVer {
+    /// FNV-1a (64-bit) over the 16-byte little-endian serialization, so it covers every
+    /// field including the commit and doesn't depend on host endianness. The algorithm
+    /// and input are fixed: this value will not change across crate releases.
+    pub const fn fingerprint(&self) -> u64 {
+        let bytes = self.to_le_bytes();
+        let mut hash = FNV_OFFSET;
+        let mut i = 0;
+        while i < bytes.len() {
+            hash ^= bytes[i] as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+            i += 1;
+        }
+        hash
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_fingerprint() {
+        // pinned: these must never change
+        assert_eq!(SemVer::from_str("v0.9.8-760-gabcd1234").unwrap().fingerprint(), 0x38c7_b457_8c43_822d);
+        assert_eq!(SemVer::new(0, 0, 0).fingerprint(), 0x8820_1fb9_60ff_6465);
+        let a = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let b = SemVer { commit: Some(0xabcd1235), ..a };
+        assert_ne!(a.fingerprint(), b.fingerprint());
+        assert_ne!(a.fingerprint(), SemVer { commit: None, ..a }.fingerprint());
+    }
+}
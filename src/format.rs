@@ -0,0 +1,106 @@
+//! Configurable text forms, so logs, filenames and UI strings all come from one API
+//! instead of string surgery on the `Display` output.
+use core::fmt;
+use crate::SemVer;
+
+/// Builds a text form of a version. The default matches `Display`.
+///
+/// ```
+/// # use xous_semver::{SemVer, VersionFormat};
+/// let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+/// let file = VersionFormat::new().prefix(false).separator("_").commit(false);
+/// assert_eq!(file.display(&ver).to_string(), "0_9_8-760");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionFormat {
+    prefix: bool,
+    separator: &'static str,
+    extra: bool,
+    commit: bool,
+    commit_width: usize,
+}
+
+impl Default for VersionFormat {
+    fn default() -> Self {
+        VersionFormat { prefix: true, separator: ".", extra: true, commit: true, commit_width: 0 }
+    }
+}
+
+impl VersionFormat {
+    pub fn new() -> Self {
+        VersionFormat::default()
+    }
+    /// Whether to start with `v`.
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
+    }
+    /// What goes between `maj`, `min` and `rev`; `.` by default.
+    pub fn separator(mut self, separator: &'static str) -> Self {
+        self.separator = separator;
+        self
+    }
+    /// Whether to include `-extra`.
+    pub fn extra(mut self, extra: bool) -> Self {
+        self.extra = extra;
+        self
+    }
+    /// Whether to include `-gcommit` when there is a commit.
+    pub fn commit(mut self, commit: bool) -> Self {
+        self.commit = commit;
+        self
+    }
+    /// Zero-pads the commit to `width` hex digits, such as 8 for `git describe`'s
+    /// default. The default, 0, prints no leading zeros.
+    pub fn commit_width(mut self, width: usize) -> Self {
+        self.commit_width = width;
+        self
+    }
+    /// `ver` in this format, for use with `format!` or `to_string`.
+    pub fn display<'a>(&self, ver: &'a SemVer) -> Formatted<'a> {
+        Formatted { ver, format: *self }
+    }
+}
+
+/// A version paired with a `VersionFormat`, from `VersionFormat::display`.
+#[derive(Debug, Clone, Copy)]
+pub struct Formatted<'a> {
+    ver: &'a SemVer,
+    format: VersionFormat,
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (ver, format) = (self.ver, &self.format);
+        if format.prefix {
+            f.write_str("v")?;
+        }
+        write!(f, "{}{sep}{}{sep}{}", ver.maj, ver.min, ver.rev, sep = format.separator)?;
+        if format.extra {
+            write!(f, "-{}", ver.extra)?;
+        }
+        if let (true, Some(commit)) = (format.commit, ver.commit) {
+            write!(f, "-g{:0width$x}", commit, width = format.commit_width)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_version_format() {
+        let ver = SemVer::from_str("v0.9.8-760-g00cd1234").unwrap();
+        assert_eq!(VersionFormat::new().display(&ver).to_string(), ver.to_string());
+        assert_eq!(VersionFormat::new().commit_width(8).display(&ver).to_string(), "v0.9.8-760-g00cd1234");
+        assert_eq!(VersionFormat::new().display(&ver).to_string(), "v0.9.8-760-gcd1234");
+        assert_eq!(VersionFormat::new().extra(false).commit(false).display(&ver).to_string(), "v0.9.8");
+        assert_eq!(
+            VersionFormat::new().prefix(false).separator("_").commit_width(8).display(&ver).to_string(),
+            "0_9_8-760-g00cd1234"
+        );
+        let release = SemVer::new(1, 2, 3);
+        assert_eq!(VersionFormat::new().commit_width(8).display(&release).to_string(), "v1.2.3-0");
+    }
+}
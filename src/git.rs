@@ -0,0 +1,515 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::{ParseError, SemVer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GitError {
+    /// The `git` process could not be spawned, typically `NotFound` when git isn't in `PATH`.
+    Exec(io::ErrorKind),
+    /// `git describe` exited unsuccessfully for a reason other than missing tags, e.g.
+    /// because the directory is not a repository. `status` is `None` if git was killed
+    /// by a signal.
+    Failed { status: Option<i32>, stderr: String },
+    /// The repository has no tags reachable from HEAD (or none matching the query).
+    NoTags,
+    /// libgit2 could not read the repository.
+    #[cfg(feature="git2")]
+    Library(String),
+    /// No line of the `git describe` output parsed as a version. `skipped` holds every
+    /// non-empty line that was rejected (last line first), and `reason` is the parse
+    /// error of the most recent candidate.
+    NoVersion { skipped: Vec<String>, reason: ParseError },
+}
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GitError::Exec(kind) => write!(f, "failed to execute git: {}", kind),
+            GitError::Failed { status: Some(code), stderr } => write!(f, "git exited with status {}: {}", code, stderr.trim()),
+            GitError::Failed { status: None, stderr } => write!(f, "git was terminated: {}", stderr.trim()),
+            GitError::NoTags => write!(f, "no tags found; tag a release such as `git tag v0.1.0`"),
+            #[cfg(feature="git2")]
+            GitError::Library(e) => write!(f, "libgit2: {}", e),
+            GitError::NoVersion { skipped, reason } => {
+                write!(f, "no version found in git output ({})", reason)?;
+                for line in skipped {
+                    write!(f, "\n  skipped: {}", line)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+impl std::error::Error for GitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GitError::NoVersion { reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Everything `git describe` says about the working tree, not just the version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitInfo {
+    pub version: SemVer,
+    /// The working tree has uncommitted changes to tracked files. Release tooling should
+    /// refuse to stamp images built from such a tree.
+    pub dirty: bool,
+    /// The checked-out branch, or `None` on a detached HEAD (as in most CI checkouts).
+    /// Always `None` when parsed from describe output alone.
+    pub branch: Option<String>,
+    /// Committer date of HEAD, in seconds since the unix epoch, or `SOURCE_DATE_EPOCH` when
+    /// that is set. Always `None` when parsed from describe output alone.
+    pub timestamp: Option<u64>,
+}
+
+/// Options for `git describe`, for when the plain `from_git*` calls aren't enough. In a
+/// monorepo, each component can follow its own tag stream:
+///
+/// ```no_run
+/// # use xous_semver::GitQuery;
+/// let soc = GitQuery::new().matching("soc-v*").describe()?;
+/// # Ok::<(), xous_semver::GitError>(())
+/// ```
+///
+/// Tags selected this way still need to parse as a version once any prefix before the
+/// `v` is stripped, so `soc-v1.2.3` is read as `v1.2.3`.
+#[derive(Debug, Clone, Default)]
+pub struct GitQuery {
+    pub(crate) dir: Option<PathBuf>,
+    pub(crate) matching: Vec<String>,
+    pub(crate) excluding: Vec<String>,
+    fallback: Fallback,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+enum Fallback {
+    #[default]
+    None,
+    CargoPkgVersion,
+    Version(SemVer),
+}
+
+impl GitQuery {
+    pub fn new() -> Self {
+        GitQuery::default()
+    }
+    /// Describes the repository at `path` instead of the current directory.
+    pub fn dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.dir = Some(path.as_ref().to_path_buf());
+        self
+    }
+    /// Only consider tags matching the glob `pattern`. May be given more than once.
+    pub fn matching(mut self, pattern: &str) -> Self {
+        self.matching.push(pattern.to_string());
+        self
+    }
+    /// Never consider tags matching the glob `pattern`. May be given more than once.
+    pub fn excluding(mut self, pattern: &str) -> Self {
+        self.excluding.push(pattern.to_string());
+        self
+    }
+    /// If git can't produce a version (no `.git` in a source tarball, no git installed, no
+    /// tags), use `CARGO_PKG_VERSION` instead of failing. The git error is still returned
+    /// if that variable is unset or isn't a plain `MAJ.MIN.REV`.
+    pub fn fallback_to_cargo(mut self) -> Self {
+        self.fallback = Fallback::CargoPkgVersion;
+        self
+    }
+    /// If git can't produce a version, use `version` instead of failing.
+    pub fn fallback_to(mut self, version: SemVer) -> Self {
+        self.fallback = Fallback::Version(version);
+        self
+    }
+    pub fn describe(&self) -> Result<GitInfo, GitError> {
+        self.or_fallback(self.describe_subprocess())
+    }
+    /// Like `describe`, but reads the repository with libgit2 instead of spawning `git`,
+    /// for CI containers and Windows runners that have no `git` in `PATH`.
+    #[cfg(feature="git2")]
+    pub fn describe_git2(&self) -> Result<GitInfo, GitError> {
+        self.or_fallback(self.describe_libgit())
+    }
+    fn or_fallback(&self, result: Result<GitInfo, GitError>) -> Result<GitInfo, GitError> {
+        let err = match result {
+            Ok(info) => return Ok(info),
+            Err(e) => e,
+        };
+        let version = match self.fallback {
+            Fallback::None => None,
+            Fallback::CargoPkgVersion => std::env::var("CARGO_PKG_VERSION").ok()
+                .and_then(|v| SemVer::from_cargo_version(&v).ok()),
+            Fallback::Version(version) => Some(version),
+        };
+        match version {
+            Some(version) => Ok(GitInfo { version, dirty: false, branch: None, timestamp: source_date_epoch() }),
+            None => Err(err),
+        }
+    }
+    fn describe_subprocess(&self) -> Result<GitInfo, GitError> {
+        let mut cmd = self.command();
+        // a fixed abbreviation keeps the output independent of repository size
+        cmd.args(["describe", "--tags", "--dirty", "--abbrev=8"]);
+        for pattern in &self.matching {
+            cmd.arg("--match").arg(pattern);
+        }
+        for pattern in &self.excluding {
+            cmd.arg("--exclude").arg(pattern);
+        }
+        let output = cmd.output().map_err(|e| GitError::Exec(e.kind()))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            return Err(if is_no_tags(&stderr) {
+                GitError::NoTags
+            } else {
+                GitError::Failed { status: output.status.code(), stderr }
+            });
+        }
+        let mut info = self.parse(&String::from_utf8_lossy(&output.stdout))?;
+        info.branch = self.branch()?;
+        info.timestamp = match source_date_epoch() {
+            Some(epoch) => Some(epoch),
+            None => self.timestamp()?,
+        };
+        Ok(info)
+    }
+    fn timestamp(&self) -> Result<Option<u64>, GitError> {
+        let output = self.command()
+            .args(["log", "-1", "--format=%ct", "HEAD"])
+            .output()
+            .map_err(|e| GitError::Exec(e.kind()))?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim().parse().ok())
+    }
+    fn branch(&self) -> Result<Option<String>, GitError> {
+        // exits with status 1 and prints nothing on a detached HEAD
+        let output = self.command()
+            .args(["symbolic-ref", "--short", "-q", "HEAD"])
+            .output()
+            .map_err(|e| GitError::Exec(e.kind()))?;
+        let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(branch).filter(|b| output.status.success() && !b.is_empty()))
+    }
+    // git is invoked directly rather than through `sh -c`/`cmd /C`, so that paths and
+    // arguments don't need shell quoting
+    fn command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        // `is_no_tags` matches git's English messages, so keep them untranslated
+        cmd.env("LC_ALL", "C").env("LANGUAGE", "C");
+        if let Some(dir) = &self.dir {
+            cmd.arg("-C").arg(dir);
+        }
+        cmd
+    }
+    #[cfg(feature="git2")]
+    fn describe_libgit(&self) -> Result<GitInfo, GitError> {
+        let mut info = self.parse(&crate::libgit::describe(self)?)?;
+        info.branch = crate::libgit::branch(self)?;
+        info.timestamp = match source_date_epoch() {
+            Some(epoch) => Some(epoch),
+            None => crate::libgit::timestamp(self)?,
+        };
+        Ok(info)
+    }
+    fn parse(&self, output: &str) -> Result<GitInfo, GitError> {
+        if self.matching.is_empty() {
+            GitInfo::from_git_output(output)
+        } else {
+            // matched tags usually carry a component prefix such as `soc-`
+            GitInfo::from_git_output(&strip_tag_prefix(output))
+        }
+    }
+}
+
+/// The `SOURCE_DATE_EPOCH` of a reproducible build, if set. Any timestamp this crate
+/// captures comes from here in preference to git or the clock.
+pub fn source_date_epoch() -> Option<u64> {
+    parse_epoch(std::env::var("SOURCE_DATE_EPOCH").ok())
+}
+
+fn parse_epoch(value: Option<String>) -> Option<u64> {
+    value?.trim().parse().ok()
+}
+
+// the messages git uses for "nothing to describe with", across versions
+fn is_no_tags(stderr: &str) -> bool {
+    ["No names found", "No tags can describe", "cannot describe"].iter().any(|m| stderr.contains(m))
+}
+
+// cuts each line at the last `-v` followed by a digit, so a prefix that itself
+// contains `-v`, such as `sdk-vendor-`, is removed whole
+fn strip_tag_prefix(output: &str) -> String {
+    output.lines()
+        .map(|line| {
+            line.rmatch_indices("-v")
+                .map(|(i, _)| &line[i + 1..])
+                .find(|rest| rest[1..].starts_with(|c: char| c.is_ascii_digit()))
+                .unwrap_or(line)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl GitInfo {
+    /// Describes the repository containing the current directory.
+    pub fn from_git() -> Result<Self, GitError> {
+        GitQuery::new().describe()
+    }
+    /// Describes the repository at `path`, which need not be the current directory.
+    pub fn from_git_in<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        GitQuery::new().dir(path).describe()
+    }
+    /// Parses the stdout of `git describe --dirty`, tolerating wrappers that print warnings
+    /// or other chatter around the describe line. The last non-empty line that parses wins.
+    pub fn from_git_output(output: &str) -> Result<Self, GitError> {
+        let mut skipped = Vec::new();
+        let mut reason = ParseError::Empty;
+        for line in output.lines().rev().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let (line_ver, dirty) = match line.strip_suffix("-dirty") {
+                Some(stripped) => (stripped, true),
+                None => (line, false),
+            };
+            match SemVer::from_str(line_ver) {
+                Ok(version) => return Ok(GitInfo { version, dirty, branch: None, timestamp: None }),
+                Err(e) => {
+                    reason = e;
+                    skipped.push(line.to_string());
+                }
+            }
+        }
+        Err(GitError::NoVersion { skipped, reason })
+    }
+}
+
+impl SemVer {
+    /// Describes the repository containing the current directory. Use `GitInfo::from_git`
+    /// to also learn whether the working tree is dirty.
+    pub fn from_git() -> Result<Self, GitError> {
+        GitInfo::from_git().map(|info| info.version)
+    }
+    /// Describes the repository at `path`, which need not be the current directory.
+    pub fn from_git_in<P: AsRef<Path>>(path: P) -> Result<Self, GitError> {
+        GitInfo::from_git_in(path).map(|info| info.version)
+    }
+    /// Describes the repository containing the current directory, including the dirty
+    /// flag and branch name.
+    pub fn from_git_full() -> Result<GitInfo, GitError> {
+        GitInfo::from_git()
+    }
+    /// Describes the current directory using only tags matching the glob `pattern`, such
+    /// as `soc-v*`. See `GitQuery` for more options.
+    pub fn from_git_matching(pattern: &str) -> Result<Self, GitError> {
+        GitQuery::new().matching(pattern).describe().map(|info| info.version)
+    }
+    /// Parses the stdout of `git describe`; see `GitInfo::from_git_output`.
+    pub fn from_git_output(output: &str) -> Result<Self, GitError> {
+        GitInfo::from_git_output(output).map(|info| info.version)
+    }
+    /// The release tag for this version, e.g. `soc-v0.9.8` for the prefix `soc-`. Only
+    /// `maj.min.rev` are used: `extra` and the commit are what `git describe` appends
+    /// relative to the tag, so putting them in the tag itself would confuse it.
+    pub fn to_tag_string(&self, prefix: &str) -> String {
+        format!("{}v{}.{}.{}", prefix, self.maj, self.min, self.rev)
+    }
+    /// Creates an annotated tag `to_tag_string(prefix)` on HEAD of the repository at
+    /// `path`, GPG-signed if `signed`. Returns the tag name. The tag is not pushed.
+    pub fn create_git_tag<P: AsRef<Path>>(&self, path: P, prefix: &str, message: &str, signed: bool) -> Result<String, GitError> {
+        let tag = self.to_tag_string(prefix);
+        let output = Command::new("git")
+            .arg("-C").arg(path.as_ref())
+            .args(["tag", if signed { "-s" } else { "-a" }, "-m", message, "--", &tag])
+            .output()
+            .map_err(|e| GitError::Exec(e.kind()))?;
+        if !output.status.success() {
+            return Err(GitError::Failed {
+                status: output.status.code(),
+                stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            });
+        }
+        Ok(tag)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::ffi::OsStr;
+
+    // runs git with a fixed identity, so tests don't depend on the user's config
+    pub(crate) fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .arg("-C").arg(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com", "-c", "commit.gpgsign=false"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+    /// A fresh repository with a single commit, unique to `name`.
+    pub(crate) fn scratch_repo(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xous-semver-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        git(&dir, &["init", "-q"]);
+        std::fs::write(dir.join("file"), "one").unwrap();
+        git(&dir, &["add", "file"]);
+        git(&dir, &["commit", "-q", "-m", "one"]);
+        dir
+    }
+    #[test]
+    fn test_git_in() {
+        let repo = scratch_repo("git-in");
+        git(&repo, &["tag", "v1.2.3"]);
+        assert_eq!(SemVer::from_git_in(&repo).unwrap(), SemVer::new(1, 2, 3));
+        std::fs::write(repo.join("file"), "two").unwrap();
+        git(&repo, &["commit", "-q", "-am", "two"]);
+        let head = u32::from_str_radix(&git(&repo, &["rev-parse", "--short=8", "HEAD"]), 16).unwrap();
+        let ver = SemVer::from_git_in(&repo).unwrap();
+        assert_eq!(ver, SemVer { extra: 1, commit: Some(head), ..SemVer::new(1, 2, 3) });
+        assert!(!GitInfo::from_git_in(&repo).unwrap().dirty);
+        std::fs::write(repo.join("file"), "three").unwrap();
+        git(&repo, &["checkout", "-q", "-b", "feature/x"]);
+        let committed = source_date_epoch()
+            .unwrap_or_else(|| git(&repo, &["log", "-1", "--format=%ct"]).parse().unwrap());
+        assert_eq!(
+            GitInfo::from_git_in(&repo).unwrap(),
+            GitInfo { version: ver, dirty: true, branch: Some("feature/x".to_string()), timestamp: Some(committed) }
+        );
+        git(&repo, &["checkout", "-q", "--detach"]);
+        assert_eq!(GitInfo::from_git_in(&repo).unwrap().branch, None);
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_git_matching() {
+        let repo = scratch_repo("git-matching");
+        git(&repo, &["tag", "soc-v1.2.3"]);
+        git(&repo, &["tag", "ec-v0.4.0"]);
+        git(&repo, &["tag", "v9.0.0-rc"]);
+        let soc = GitQuery::new().dir(&repo).matching("soc-v*").describe().unwrap();
+        assert_eq!(soc.version, SemVer::new(1, 2, 3));
+        let ec = GitQuery::new().dir(&repo).matching("*-v*").excluding("soc-*").describe().unwrap();
+        assert_eq!(ec.version, SemVer::new(0, 4, 0));
+        assert_eq!(GitQuery::new().dir(&repo).matching("nope-v*").describe(), Err(GitError::NoTags));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_git_errors() {
+        let repo = scratch_repo("git-errors");
+        assert_eq!(SemVer::from_git_in(&repo), Err(GitError::NoTags));
+        let not_repo = std::env::temp_dir().join(format!("xous-semver-{}-not-a-repo", std::process::id()));
+        std::fs::create_dir_all(&not_repo).unwrap();
+        match SemVer::from_git_in(&not_repo) {
+            Err(GitError::Failed { status: Some(code), stderr }) => {
+                assert_ne!(code, 0);
+                assert!(stderr.contains("not a git repository"), "{}", stderr);
+            }
+            // a repository further up the tree (e.g. a temp dir inside a checkout)
+            Ok(_) | Err(GitError::NoTags) => {}
+            other => panic!("{:?}", other),
+        }
+        std::fs::remove_dir_all(&not_repo).unwrap();
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_strip_tag_prefix() {
+        assert_eq!(strip_tag_prefix("soc-v1.2.3-4-gabcd1234\n"), "v1.2.3-4-gabcd1234");
+        assert_eq!(strip_tag_prefix("sdk-vendor-v1.0.0-3-gabcd1234"), "v1.0.0-3-gabcd1234");
+        assert_eq!(strip_tag_prefix("v2-video-v0.4.0-dirty"), "v0.4.0-dirty");
+        assert_eq!(strip_tag_prefix("v1.2.3-4-gabcd1234"), "v1.2.3-4-gabcd1234");
+        assert_eq!(strip_tag_prefix("warning: x\nec-v0.4.0"), "warning: x\nv0.4.0");
+        let repo = scratch_repo("git-vendor-prefix");
+        git(&repo, &["tag", "sdk-vendor-v1.0.0"]);
+        let sdk = GitQuery::new().dir(&repo).matching("sdk-vendor-v*").describe().unwrap();
+        assert_eq!(sdk.version, SemVer::new(1, 0, 0));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_git_locale() {
+        let cmd = GitQuery::new().command();
+        let envs: Vec<_> = cmd.get_envs().collect();
+        assert!(envs.contains(&(OsStr::new("LC_ALL"), Some(OsStr::new("C")))));
+        assert!(envs.contains(&(OsStr::new("LANGUAGE"), Some(OsStr::new("C")))));
+    }
+    #[test]
+    fn test_create_tag() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.to_tag_string(""), "v0.9.8");
+        assert_eq!(ver.to_tag_string("soc-"), "soc-v0.9.8");
+
+        let repo = scratch_repo("create-tag");
+        git(&repo, &["config", "user.name", "test"]);
+        git(&repo, &["config", "user.email", "test@example.com"]);
+        assert_eq!(ver.create_git_tag(&repo, "soc-", "release 0.9.8", false), Ok("soc-v0.9.8".to_string()));
+        assert_eq!(git(&repo, &["cat-file", "-t", "soc-v0.9.8"]), "tag");
+        let described = GitQuery::new().dir(&repo).matching("soc-v*").describe().unwrap();
+        assert_eq!(described.version, SemVer::new(0, 9, 8));
+        assert!(matches!(
+            ver.create_git_tag(&repo, "soc-", "again", false),
+            Err(GitError::Failed { status: Some(_), .. })
+        ));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_git_fallback() {
+        let repo = scratch_repo("git-fallback");
+        let fallback = SemVer::new(0, 0, 1);
+        assert_eq!(GitQuery::new().dir(&repo).describe(), Err(GitError::NoTags));
+        assert_eq!(GitQuery::new().dir(&repo).fallback_to(fallback).describe().unwrap().version, fallback);
+        let cargo = SemVer::from_cargo_version(env!("CARGO_PKG_VERSION")).unwrap();
+        assert_eq!(GitQuery::new().dir(&repo).fallback_to_cargo().describe().unwrap().version, cargo);
+        git(&repo, &["tag", "v1.2.3"]);
+        assert_eq!(GitQuery::new().dir(&repo).fallback_to(fallback).describe().unwrap().version, SemVer::new(1, 2, 3));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_source_date_epoch() {
+        assert_eq!(parse_epoch(Some("1700000000".to_string())), Some(1_700_000_000));
+        assert_eq!(parse_epoch(Some(" 1700000000\n".to_string())), Some(1_700_000_000));
+        assert_eq!(parse_epoch(Some("yesterday".to_string())), None);
+        assert_eq!(parse_epoch(Some("-1".to_string())), None);
+        assert_eq!(parse_epoch(None), None);
+    }
+    #[test]
+    fn test_gitver() {
+        let gitver = SemVer::from_git();
+        println!("{:?}", gitver);
+        assert!(gitver.is_ok());
+    }
+    #[test]
+    fn test_git_output() {
+        let out = "warning: refname 'v0.9.8' is ambiguous.\nv0.9.8-760-gabcd1234\n\n";
+        assert_eq!(
+            SemVer::from_git_output(out).unwrap(),
+            SemVer::from_str("v0.9.8-760-gabcd1234").unwrap()
+        );
+        let out = "v0.9.8-760-gabcd1234\r\nwrapper: took 3ms\r\n";
+        assert_eq!(
+            SemVer::from_git_output(out).unwrap(),
+            SemVer::from_str("v0.9.8-760-gabcd1234").unwrap()
+        );
+        assert_eq!(
+            SemVer::from_git_output("fatal: No names found\n  \nhint: tag something\n"),
+            Err(GitError::NoVersion {
+                skipped: vec!["hint: tag something".to_string(), "fatal: No names found".to_string()],
+                reason: ParseError::MissingPrefix,
+            })
+        );
+        assert_eq!(
+            GitInfo::from_git_output("v0.9.8-760-gabcd1234-dirty\n").unwrap(),
+            GitInfo { version: SemVer::from_str("v0.9.8-760-gabcd1234").unwrap(), dirty: true, branch: None, timestamp: None }
+        );
+        assert!(!GitInfo::from_git_output("v0.9.8\n").unwrap().dirty);
+        assert_eq!(
+            SemVer::from_git_output(""),
+            Err(GitError::NoVersion { skipped: vec![], reason: ParseError::Empty })
+        );
+        assert_eq!(
+            SemVer::from_str_last_line("noise\nv1.2.3\n"),
+            SemVer::from_str("v1.2.3")
+        );
+        assert_eq!(SemVer::from_str_last_line("noise\n"), Err(ParseError::MissingPrefix));
+        assert_eq!(SemVer::from_str_last_line(" \n"), Err(ParseError::Empty));
+    }
+}
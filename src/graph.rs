@@ -0,0 +1,150 @@
+//! Graphviz export of compatibility relationships, for auditing declared upgrade windows
+//! before a manifest ships.
+use crate::{CompatMatrix, SemVer, VersionRange};
+use std::fmt::Write;
+
+// the contents of a DOT quoted string, with quotes, backslashes and line breaks escaped
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Renders the relation `compatible(from, to)` over `versions` as a DOT digraph. Every
+/// version becomes a node, and an edge `from -> to` is drawn for each ordered pair of
+/// distinct versions the predicate accepts. Nodes are emitted in ascending order so the
+/// output is stable across runs.
+pub fn to_dot<F>(name: &str, versions: &[SemVer], compatible: F) -> String
+where
+    F: Fn(&SemVer, &SemVer) -> bool,
+{
+    let mut sorted: Vec<&SemVer> = versions.iter().collect();
+    // `dedup` compares commits, so the sort has to as well for duplicates to be adjacent
+    sorted.sort_by(|a, b| a.cmp_with_commit(b));
+    sorted.dedup();
+    let mut dot = String::new();
+    let label = |ver: &SemVer| escape(&ver.to_string());
+    writeln!(dot, "digraph \"{}\" {{", escape(name)).unwrap();
+    for ver in &sorted {
+        writeln!(dot, "    \"{}\";", label(ver)).unwrap();
+    }
+    for from in &sorted {
+        for to in &sorted {
+            if from != to && compatible(from, to) {
+                writeln!(dot, "    \"{}\" -> \"{}\";", label(from), label(to)).unwrap();
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Renders requirement windows as a DOT upgrade graph: each entry pairs a release with
+/// the range of versions it may be installed over, and an edge `from -> release` is
+/// drawn for every listed release `from` inside that range.
+pub fn windows_to_dot(name: &str, windows: &[(SemVer, VersionRange)]) -> String {
+    let releases: Vec<SemVer> = windows.iter().map(|(release, _)| *release).collect();
+    to_dot(name, &releases, |from, to| {
+        windows.iter().any(|(release, window)| release == to && window.contains(from))
+    })
+}
+
+/// Renders the rules of a compatibility matrix as DOT, with a node per EC and SoC range
+/// and an edge per rule. Edges are labelled with the rule's index and verdict, since
+/// the first matching rule wins; forbidden pairs are dashed.
+pub fn compat_to_dot(name: &str, matrix: &CompatMatrix) -> String {
+    let mut nodes: Vec<String> = Vec::new();
+    for rule in matrix.rules() {
+        for node in [format!("ec {}", rule.ec), format!("soc {}", rule.soc)] {
+            if !nodes.contains(&node) {
+                nodes.push(node);
+            }
+        }
+    }
+    let mut dot = String::new();
+    writeln!(dot, "digraph \"{}\" {{", escape(name)).unwrap();
+    for node in &nodes {
+        writeln!(dot, "    \"{}\";", escape(node)).unwrap();
+    }
+    for (i, rule) in matrix.rules().enumerate() {
+        let (verdict, style) = if rule.allowed { ("allow", "") } else { ("forbid", ", style=dashed") };
+        writeln!(
+            dot, "    \"ec {}\" -> \"soc {}\" [label=\"{}: {}\"{}];",
+            escape(&rule.ec.to_string()), escape(&rule.soc.to_string()), i, verdict, style
+        ).unwrap();
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_dot() {
+        let versions = [
+            SemVer::from_str("v0.9.9").unwrap(),
+            SemVer::from_str("v0.9.8").unwrap(),
+            SemVer::from_str("v1.0.0").unwrap(),
+            SemVer::from_str("v0.9.8").unwrap(),
+        ];
+        // only upgrades within the same major.minor series are allowed
+        let dot = to_dot("soc", &versions, |a, b| a < b && a.maj == b.maj && a.min == b.min);
+        assert_eq!(dot, "digraph \"soc\" {\n\
+            \x20   \"v0.9.8-0\";\n\
+            \x20   \"v0.9.9-0\";\n\
+            \x20   \"v1.0.0-0\";\n\
+            \x20   \"v0.9.8-0\" -> \"v0.9.9-0\";\n\
+            }\n");
+        let dot = to_dot("soc \"beta\"\\\n", &versions[..1], |_, _| false);
+        assert_eq!(dot, "digraph \"soc \\\"beta\\\"\\\\\\n\" {\n    \"v0.9.9-0\";\n}\n");
+        assert_eq!(escape("a\r\nb"), "a\\nb");
+    }
+    #[test]
+    fn test_dot_dedup() {
+        // duplicates of one version must collapse even when another build sorts between them
+        let v = |s| SemVer::from_str(s).unwrap();
+        let versions = [v("v1.0.0-0-gaaaa"), v("v1.0.0-0-gbbbb"), v("v1.0.0-0-gaaaa")];
+        let dot = to_dot("dup", &versions, |_, _| false);
+        assert_eq!(dot, "digraph \"dup\" {\n    \"v1.0.0-0-gaaaa\";\n    \"v1.0.0-0-gbbbb\";\n}\n");
+    }
+    #[test]
+    fn test_windows_dot() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let windows = [
+            (v("v0.9.8"), VersionRange::half_open(v("v0.9.0"), v("v0.9.8"))),
+            (v("v0.9.9"), VersionRange::half_open(v("v0.9.0"), v("v0.9.9"))),
+            (v("v1.0.0"), VersionRange::inclusive(v("v0.9.9"), v("v0.9.9"))),
+        ];
+        assert_eq!(windows_to_dot("soc", &windows), "digraph \"soc\" {\n\
+            \x20   \"v0.9.8-0\";\n\
+            \x20   \"v0.9.9-0\";\n\
+            \x20   \"v1.0.0-0\";\n\
+            \x20   \"v0.9.8-0\" -> \"v0.9.9-0\";\n\
+            \x20   \"v0.9.9-0\" -> \"v1.0.0-0\";\n\
+            }\n");
+    }
+    #[test]
+    fn test_compat_dot() {
+        use crate::CompatRule;
+        let v = |s| SemVer::from_str(s).unwrap();
+        let nine = VersionRange::half_open(v("v0.9.0"), v("v0.10.0"));
+        let ten = VersionRange::half_open(v("v0.10.0"), v("v1.0.0"));
+        let rules = [CompatRule::allow(nine, nine), CompatRule::forbid(nine, ten)];
+        assert_eq!(compat_to_dot("ec-soc", &CompatMatrix::new(&rules)), "digraph \"ec-soc\" {\n\
+            \x20   \"ec [v0.9.0-0, v0.10.0-0)\";\n\
+            \x20   \"soc [v0.9.0-0, v0.10.0-0)\";\n\
+            \x20   \"soc [v0.10.0-0, v1.0.0-0)\";\n\
+            \x20   \"ec [v0.9.0-0, v0.10.0-0)\" -> \"soc [v0.9.0-0, v0.10.0-0)\" [label=\"0: allow\"];\n\
+            \x20   \"ec [v0.9.0-0, v0.10.0-0)\" -> \"soc [v0.10.0-0, v1.0.0-0)\" [label=\"1: forbid\", style=dashed];\n\
+            }\n");
+    }
+}
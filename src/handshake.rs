@@ -0,0 +1,149 @@
+//! A version handshake for IPC services, so clients and servers agree on compatibility
+//! with the same rules on both ends.
+//!
+//! The client sends a `HandshakeRequest`: its version then the oldest server it
+//! accepts, 32 bytes of fixed binary serializations. The server answers with a
+//! `HandshakeResponse`: its version then a little-endian verdict word, 20 bytes.
+use crate::{Error, SemVer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Accepted,
+    /// The server is older than the client's minimum.
+    ServerTooOld,
+    /// The client is older than the server's minimum.
+    ClientTooOld,
+}
+
+impl Verdict {
+    fn to_word(self) -> u32 {
+        match self {
+            Verdict::Accepted => 0,
+            Verdict::ServerTooOld => 1,
+            Verdict::ClientTooOld => 2,
+        }
+    }
+    fn from_word(word: u32) -> Result<Self, Error> {
+        match word {
+            0 => Ok(Verdict::Accepted),
+            1 => Ok(Verdict::ServerTooOld),
+            2 => Ok(Verdict::ClientTooOld),
+            _ => Err(Error::Malformed("unknown handshake verdict")),
+        }
+    }
+}
+
+// the one compatibility rule both sides apply; commits are not compared
+fn verdict(client: &SemVer, min_client: &SemVer, server: &SemVer, min_server: &SemVer) -> Verdict {
+    if server < min_server {
+        Verdict::ServerTooOld
+    } else if client < min_client {
+        Verdict::ClientTooOld
+    } else {
+        Verdict::Accepted
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeRequest {
+    pub client: SemVer,
+    pub min_server: SemVer,
+}
+
+impl HandshakeRequest {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 2 * SemVer::SERIALIZED_LEN;
+
+    /// Server side: answers the request as server version `server`, which requires
+    /// clients of at least `min_client`.
+    pub fn answer(&self, server: SemVer, min_client: &SemVer) -> HandshakeResponse {
+        HandshakeResponse { server, verdict: verdict(&self.client, min_client, &server, &self.min_server) }
+    }
+    /// Client side: the verdict to act on. The server's verdict stands unless it claims
+    /// acceptance while its own version is below our minimum, which is then reported as
+    /// `ServerTooOld` rather than trusted.
+    pub fn check(&self, response: &HandshakeResponse) -> Verdict {
+        match response.verdict {
+            Verdict::Accepted if response.server < self.min_server => Verdict::ServerTooOld,
+            verdict => verdict,
+        }
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..HandshakeRequest::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: HandshakeRequest::SERIALIZED_LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.client));
+        dest[16..].copy_from_slice(&<[u8; 16]>::from(&self.min_server));
+        Ok(HandshakeRequest::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<HandshakeRequest, Error> {
+        let (client, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (min_server, _) = rest.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        Ok(HandshakeRequest {
+            client: SemVer::from_bytes_strict(client)?,
+            min_server: SemVer::from_bytes_strict(min_server)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandshakeResponse {
+    pub server: SemVer,
+    pub verdict: Verdict,
+}
+
+impl HandshakeResponse {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = SemVer::SERIALIZED_LEN + 4;
+
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..HandshakeResponse::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: HandshakeResponse::SERIALIZED_LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.server));
+        dest[16..].copy_from_slice(&self.verdict.to_word().to_le_bytes());
+        Ok(HandshakeResponse::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<HandshakeResponse, Error> {
+        let (server, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (verdict, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        Ok(HandshakeResponse {
+            server: SemVer::from_bytes_strict(server)?,
+            verdict: Verdict::from_word(u32::from_le_bytes(*verdict))?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_handshake() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let request = HandshakeRequest { client: v("v0.9.8-760-gabcd1234"), min_server: v("v0.9.5") };
+        let mut wire = [0u8; 32];
+        assert_eq!(request.write_to(&mut wire), Ok(32));
+        let received = HandshakeRequest::read_from(&wire).unwrap();
+        assert_eq!(received, request);
+
+        let response = received.answer(v("v0.9.6"), &v("v0.9.0"));
+        assert_eq!(response.verdict, Verdict::Accepted);
+        let mut reply = [0u8; 20];
+        assert_eq!(response.write_to(&mut reply), Ok(20));
+        let response = HandshakeResponse::read_from(&reply).unwrap();
+        assert_eq!(request.check(&response), Verdict::Accepted);
+
+        assert_eq!(received.answer(v("v0.9.4"), &v("v0.9.0")).verdict, Verdict::ServerTooOld);
+        assert_eq!(received.answer(v("v0.9.6"), &v("v0.9.9")).verdict, Verdict::ClientTooOld);
+        // an old server that accepts anyway is still too old for the client
+        let lax = HandshakeResponse { server: v("v0.9.4"), verdict: Verdict::Accepted };
+        assert_eq!(request.check(&lax), Verdict::ServerTooOld);
+
+        reply[16] = 3;
+        assert_eq!(HandshakeResponse::read_from(&reply), Err(Error::Malformed("unknown handshake verdict")));
+        assert_eq!(HandshakeRequest::read_from(&wire[..31]), Err(Error::Truncated));
+        assert_eq!(response.write_to(&mut reply[..19]), Err(Error::BufferTooSmall { needed: 20 }));
+    }
+}
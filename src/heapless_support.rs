@@ -0,0 +1,44 @@
+//! Conversions to and from `heapless::String`, which most of our embedded string
+//! handling already uses.
+use heapless::String;
+use crate::{Error, ParseError, SemVer};
+
+impl SemVer {
+    /// The `Display` output as a `heapless::String`. Fails with `Error::BufferTooSmall`
+    /// if it doesn't fit in `N` bytes; `N` of `MAX_DISPLAY_LEN` always fits.
+    pub fn to_heapless_string<const N: usize>(&self) -> Result<String<N>, Error> {
+        let needed = self.display_len();
+        if needed > N {
+            return Err(Error::BufferTooSmall { needed });
+        }
+        let mut s = String::new();
+        self.display_pieces(|piece| s.push_str(piece)).map_err(|_| Error::BufferTooSmall { needed })?;
+        Ok(s)
+    }
+}
+
+impl<const N: usize> TryFrom<&String<N>> for SemVer {
+    type Error = ParseError;
+    fn try_from(s: &String<N>) -> Result<Self, Self::Error> {
+        SemVer::from_str(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MAX_DISPLAY_LEN;
+    #[test]
+    fn test_heapless() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let s = ver.to_heapless_string::<MAX_DISPLAY_LEN>().unwrap();
+        assert_eq!(s.as_str(), "v0.9.8-760-gabcd1234");
+        assert_eq!(SemVer::try_from(&s), Ok(ver));
+        assert_eq!(ver.to_heapless_string::<19>(), Err(Error::BufferTooSmall { needed: 20 }));
+        assert_eq!(ver.to_heapless_string::<20>().unwrap().len(), 20);
+
+        let mut bad: String<8> = String::new();
+        bad.push_str("0.9.8").unwrap();
+        assert_eq!(SemVer::try_from(&bad), Err(ParseError::MissingPrefix));
+    }
+}
@@ -0,0 +1,94 @@
+//! Hex transport of the 16-byte serialization, for text-only channels such as UART logs
+//! and JSON configs.
+use core::fmt;
+use crate::{Error, SemVer};
+
+const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+pub(crate) fn encode(bytes: &[u8], out: &mut [u8]) {
+    for (byte, pair) in bytes.iter().zip(out.chunks_exact_mut(2)) {
+        pair[0] = DIGITS[(byte >> 4) as usize];
+        pair[1] = DIGITS[(byte & 0xf) as usize];
+    }
+}
+
+pub(crate) fn decode(hex: &[u8], out: &mut [u8]) -> Result<(), Error> {
+    if hex.len() != out.len() * 2 {
+        return Err(Error::Malformed("wrong number of hex digits"));
+    }
+    for (byte, pair) in out.iter_mut().zip(hex.chunks_exact(2)) {
+        *byte = (nibble(pair[0])? << 4) | nibble(pair[1])?;
+    }
+    Ok(())
+}
+
+fn nibble(digit: u8) -> Result<u8, Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Error::Malformed("invalid hex digit")),
+    }
+}
+
+impl SemVer {
+    /// The 16-byte serialization as 32 lowercase ASCII hex digits.
+    pub fn to_hex(&self) -> [u8; 32] {
+        let mut hex = [0u8; 32];
+        encode(&<[u8; 16]>::from(self), &mut hex);
+        hex
+    }
+    /// Parses the output of `to_hex`. Upper and lower case digits are accepted.
+    pub fn from_hex(hex: &str) -> Result<SemVer, Error> {
+        let mut bytes = [0u8; 16];
+        decode(hex.trim().as_bytes(), &mut bytes)?;
+        Ok(SemVer::from(bytes))
+    }
+}
+
+/// The 16-byte serialization as 32 hex digits, matching `to_hex`, for comparing against
+/// flash or JTAG dumps. `{:#x}` adds a `0x` prefix.
+impl fmt::LowerHex for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        f.write_str(core::str::from_utf8(&self.to_hex()).map_err(|_| fmt::Error)?)
+    }
+}
+/// As `LowerHex`, with uppercase digits.
+impl fmt::UpperHex for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        let mut hex = self.to_hex();
+        hex.make_ascii_uppercase();
+        f.write_str(core::str::from_utf8(&hex).map_err(|_| fmt::Error)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_hex() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(&ver.to_hex(), b"000009000800f8023412cdab01000000");
+        assert_eq!(SemVer::from_hex("000009000800f8023412cdab01000000").unwrap(), ver);
+        assert_eq!(SemVer::from_hex("000009000800F8023412CDAB01000000\n").unwrap(), ver);
+        assert_eq!(SemVer::from_hex("00000900080"), Err(Error::Malformed("wrong number of hex digits")));
+        assert_eq!(
+            SemVer::from_hex("00000900080xf8023412cdab01000000"),
+            Err(Error::Malformed("invalid hex digit"))
+        );
+    }
+    #[test]
+    fn test_hex_fmt() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(format!("{:x}", ver), "000009000800f8023412cdab01000000");
+        assert_eq!(format!("{:#x}", ver), "0x000009000800f8023412cdab01000000");
+        assert_eq!(format!("{:X}", ver), "000009000800F8023412CDAB01000000");
+        assert_eq!(SemVer::from_hex(&format!("{:X}", ver)), Ok(ver));
+    }
+}
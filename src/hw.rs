@@ -0,0 +1,148 @@
+//! Hardware revisions, and the hardware/firmware pair that compatibility checks need.
+//!
+//! A `HwRev` is written `hwB.V.S` (board, variant, strap) and serializes to 4 bytes:
+//! board, variant, strap, then a reserved zero byte. A `DeviceIdent` serializes to the
+//! 4-byte `HwRev` then the 16-byte `SemVer`, 20 bytes in total.
+use core::fmt;
+use crate::{Error, ParseError, SemVer};
+
+/// A board revision: the board spin, an assembly variant, and the strap-resistor setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub struct HwRev {
+    pub board: u8,
+    pub variant: u8,
+    pub strap: u8,
+}
+
+impl HwRev {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 4;
+
+    pub const fn new(board: u8, variant: u8, strap: u8) -> Self {
+        HwRev { board, variant, strap }
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..HwRev::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: HwRev::SERIALIZED_LEN })?;
+        dest.copy_from_slice(&[self.board, self.variant, self.strap, 0]);
+        Ok(HwRev::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<HwRev, Error> {
+        let &[board, variant, strap, padding] = buf.first_chunk::<4>().ok_or(Error::Truncated)?;
+        if padding != 0 {
+            return Err(Error::Malformed("nonzero hardware revision padding"));
+        }
+        Ok(HwRev { board, variant, strap })
+    }
+}
+
+impl fmt::Display for HwRev {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hw{}.{}.{}", self.board, self.variant, self.strap)
+    }
+}
+/// Parses the `hwB.V.S` form.
+impl core::str::FromStr for HwRev {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let fields = s.strip_prefix("hw").ok_or(ParseError::MissingPrefix)?;
+        let mut parts = fields.split('.');
+        let mut next = |name| {
+            parts.next().ok_or(ParseError::FieldCount)?.parse::<u8>().map_err(|_| ParseError::Component(name))
+        };
+        let rev = HwRev { board: next("board")?, variant: next("variant")?, strap: next("strap")? };
+        if parts.next().is_some() {
+            return Err(ParseError::FieldCount);
+        }
+        Ok(rev)
+    }
+}
+
+/// What a device is: its hardware revision and the firmware version it runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub struct DeviceIdent {
+    pub hw: HwRev,
+    pub sw: SemVer,
+}
+
+impl DeviceIdent {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = HwRev::SERIALIZED_LEN + SemVer::SERIALIZED_LEN;
+
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..DeviceIdent::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: DeviceIdent::SERIALIZED_LEN })?;
+        self.hw.write_to(dest)?;
+        dest[4..].copy_from_slice(&<[u8; 16]>::from(&self.sw));
+        Ok(DeviceIdent::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<DeviceIdent, Error> {
+        let (hw, rest) = buf.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let (sw, _) = rest.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        Ok(DeviceIdent {
+            hw: HwRev::read_from(hw)?,
+            sw: SemVer::from_bytes_strict(sw)?,
+        })
+    }
+}
+
+impl fmt::Display for DeviceIdent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.hw, self.sw)
+    }
+}
+/// Parses the `Display` form, `hwB.V.S vX.Y.Z[-extra][-gcommit]`.
+impl core::str::FromStr for DeviceIdent {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (hw, sw) = s.trim().split_once(' ').ok_or(ParseError::FieldCount)?;
+        Ok(DeviceIdent { hw: hw.parse()?, sw: SemVer::from_str(sw)? })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_hw_rev() {
+        let rev = "hw3.1.2".parse::<HwRev>().unwrap();
+        assert_eq!(rev, HwRev::new(3, 1, 2));
+        assert_eq!(rev.to_string(), "hw3.1.2");
+        assert_eq!("".parse::<HwRev>(), Err(ParseError::Empty));
+        assert_eq!("3.1.2".parse::<HwRev>(), Err(ParseError::MissingPrefix));
+        assert_eq!("hw3.1".parse::<HwRev>(), Err(ParseError::FieldCount));
+        assert_eq!("hw3.1.2.0".parse::<HwRev>(), Err(ParseError::FieldCount));
+        assert_eq!("hw3.256.2".parse::<HwRev>(), Err(ParseError::Component("variant")));
+        assert!(HwRev::new(3, 0, 0) > HwRev::new(2, 9, 9));
+
+        let mut buf = [0u8; 4];
+        assert_eq!(rev.write_to(&mut buf), Ok(4));
+        assert_eq!(buf, [3, 1, 2, 0]);
+        assert_eq!(HwRev::read_from(&buf), Ok(rev));
+        assert_eq!(HwRev::read_from(&[3, 1, 2, 1]), Err(Error::Malformed("nonzero hardware revision padding")));
+    }
+    #[test]
+    fn test_device_ident() {
+        let ident: DeviceIdent = "hw3.1.2 v0.9.8-760-gabcd1234".parse().unwrap();
+        assert_eq!(ident.hw, HwRev::new(3, 1, 2));
+        assert_eq!(ident.sw, SemVer::from_str("v0.9.8-760-gabcd1234").unwrap());
+        assert_eq!(ident.to_string(), "hw3.1.2 v0.9.8-760-gabcd1234");
+        assert_eq!("hw3.1.2".parse::<DeviceIdent>(), Err(ParseError::FieldCount));
+
+        let mut buf = [0u8; 20];
+        assert_eq!(ident.write_to(&mut buf), Ok(20));
+        assert_eq!(DeviceIdent::read_from(&buf), Ok(ident));
+        assert_eq!(DeviceIdent::read_from(&buf[..19]), Err(Error::Truncated));
+        assert_eq!(ident.write_to(&mut buf[..19]), Err(Error::BufferTooSmall { needed: 20 }));
+    }
+}
@@ -0,0 +1,125 @@
+//! Conversions to and from the crates.io `semver` crate.
+//!
+//! `maj.min.rev` map onto `major.minor.patch`. The git-derived fields go into the build
+//! metadata as `+<extra>` or `+<extra>.g<commit>`, which `semver` ignores for precedence
+//! just as the commit is ignored by our `Ord` (`extra`, however, is significant here and
+//! not there, so `semver` will consider two dev builds of the same tag equal).
+//!
+//! Going the other way:
+//! - components above `u16::MAX` fail with `Error::Overflow`;
+//! - pre-release versions fail with `Error::Malformed`, because a pre-release sorts
+//!   *before* its release and there is no way to express that in a `SemVer`;
+//! - build metadata that isn't in the form above is ignored.
+use crate::{Error, SemVer};
+
+impl TryFrom<semver::Version> for SemVer {
+    type Error = Error;
+    fn try_from(v: semver::Version) -> Result<SemVer, Error> {
+        SemVer::try_from(&v)
+    }
+}
+impl TryFrom<&semver::Version> for SemVer {
+    type Error = Error;
+    fn try_from(v: &semver::Version) -> Result<SemVer, Error> {
+        if !v.pre.is_empty() {
+            return Err(Error::Malformed("pre-release versions are not supported"));
+        }
+        let mut ver = SemVer {
+            maj: u16::try_from(v.major).map_err(|_| Error::Overflow("maj"))?,
+            min: u16::try_from(v.minor).map_err(|_| Error::Overflow("min"))?,
+            rev: u16::try_from(v.patch).map_err(|_| Error::Overflow("rev"))?,
+            extra: 0,
+            commit: None,
+        };
+        let mut build = v.build.as_str().split('.');
+        if let Some(Ok(extra)) = build.next().map(|e| e.parse::<u16>()) {
+            let commit = match (build.next(), build.next()) {
+                (None, _) => Some(None),
+                (Some(c), None) => c.strip_prefix('g')
+                    .filter(|c| !c.is_empty() && c.len() <= 8)
+                    .and_then(|c| u32::from_str_radix(c, 16).ok())
+                    .map(Some),
+                _ => None,
+            };
+            if let Some(commit) = commit {
+                ver.extra = extra;
+                ver.commit = commit;
+            }
+        }
+        Ok(ver)
+    }
+}
+impl SemVer {
+    /// Evaluates a Cargo-style requirement such as `">=0.9.8, <1.0"` against this
+    /// version. Only `maj.min.rev` take part; `extra` and the commit are ignored, so a
+    /// dev build matches whatever its tag matches.
+    pub fn matches_req(&self, req: &semver::VersionReq) -> bool {
+        req.matches(&semver::Version::from(self))
+    }
+}
+
+impl From<SemVer> for semver::Version {
+    fn from(ver: SemVer) -> semver::Version {
+        let mut v = semver::Version::new(ver.maj as u64, ver.min as u64, ver.rev as u64);
+        let build = match ver.commit {
+            Some(commit) => format!("{}.g{:x}", ver.extra, commit),
+            None if ver.extra != 0 => format!("{}", ver.extra),
+            None => String::new(),
+        };
+        if !build.is_empty() {
+            v.build = semver::BuildMetadata::new(&build).expect("build metadata is always valid");
+        }
+        v
+    }
+}
+impl From<&SemVer> for semver::Version {
+    fn from(ver: &SemVer) -> semver::Version {
+        (*ver).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_semver_crate() {
+        let cases = [
+            ("v0.9.8-760-gabcd1234", "0.9.8+760.gabcd1234"),
+            ("v0.9.8-760", "0.9.8+760"),
+            ("v0.9.8", "0.9.8"),
+            ("v0.9.8-0-g1234", "0.9.8+0.g1234"),
+        ];
+        for (ours, theirs) in cases {
+            let ver = SemVer::from_str(ours).unwrap();
+            assert_eq!(semver::Version::from(ver).to_string(), theirs);
+            assert_eq!(SemVer::try_from(semver::Version::parse(theirs).unwrap()).unwrap(), ver);
+        }
+        // unrelated build metadata is ignored
+        assert_eq!(
+            SemVer::try_from(semver::Version::parse("1.2.3+linux.x86").unwrap()).unwrap(),
+            SemVer::from_str("v1.2.3").unwrap()
+        );
+        assert_eq!(
+            SemVer::try_from(semver::Version::parse("1.2.3+5.gxyz").unwrap()).unwrap(),
+            SemVer::from_str("v1.2.3").unwrap()
+        );
+        assert_eq!(
+            SemVer::try_from(semver::Version::parse("1.70000.3").unwrap()),
+            Err(Error::Overflow("min"))
+        );
+        assert_eq!(
+            SemVer::try_from(semver::Version::parse("1.2.3-rc.1").unwrap()),
+            Err(Error::Malformed("pre-release versions are not supported"))
+        );
+    }
+    #[test]
+    fn test_matches_req() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert!(ver.matches_req(&semver::VersionReq::parse(">=0.9.8, <1.0").unwrap()));
+        assert!(ver.matches_req(&semver::VersionReq::parse("^0.9").unwrap()));
+        assert!(ver.matches_req(&semver::VersionReq::parse("=0.9.8").unwrap()));
+        assert!(!ver.matches_req(&semver::VersionReq::parse(">0.9.8").unwrap()));
+        assert!(!ver.matches_req(&semver::VersionReq::parse("^1").unwrap()));
+        assert!(SemVer::from_str("v1.2.0").unwrap().matches_req(&semver::VersionReq::STAR));
+    }
+}
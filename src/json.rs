@@ -0,0 +1,35 @@
+//! Canonical JSON form, shared with the fleet-management backend:
+//!
+//! ```json
+//! {"maj":0,"min":9,"rev":8,"extra":760,"commit":"abcd1234"}
+//! ```
+//!
+//! Keys are always emitted in this order, and `commit` is `null` when absent.
+use crate::SemVer;
+
+impl SemVer {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("SemVer serialization cannot fail")
+    }
+    pub fn from_json(json: &str) -> Result<SemVer, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_json() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.to_json(), r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"abcd1234"}"#);
+        assert_eq!(SemVer::from_json(&ver.to_json()).unwrap(), ver);
+        let ver = SemVer::from_str("v0.9.8-760-g123abc").unwrap();
+        assert_eq!(ver.to_json(), r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"00123abc"}"#);
+        assert_eq!(SemVer::from_json(r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"123abc"}"#).unwrap(), ver);
+        let ver = SemVer::from_str("v1.0.0").unwrap();
+        assert_eq!(ver.to_json(), r#"{"maj":1,"min":0,"rev":0,"extra":0,"commit":null}"#);
+        assert!(SemVer::from_json(r#"{"maj":1,"min":0,"rev":0,"commit":"xyz"}"#).is_err());
+        assert!(SemVer::from_json(r#"{"maj":1,"min":0,"rev":0,"commit":"abcd12345"}"#).is_err());
+    }
+}
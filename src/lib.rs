@@ -1,143 +1,444 @@
+#![cfg_attr(all(not(feature="std"), not(test)), no_std)]
+
+use core::cmp::Ordering;
+use core::fmt;
+
+mod error;
+pub use error::{Error, ParseError};
+pub mod tlv;
+mod crc;
+mod table;
+mod checked;
+mod dual;
+mod hex;
+mod display;
+mod fingerprint;
+mod format;
+pub use format::{Formatted, VersionFormat};
+mod maybe;
+pub use maybe::MaybeVersion;
+mod release;
+pub use release::{DevBuild, NoCommit, NotARelease, ReleaseVersion};
+pub use display::{CommitStr, DisplayBuf, MAX_DISPLAY_LEN};
+mod csr;
+mod usb;
+pub mod uf2;
+mod dfu;
+pub use dfu::DfuSuffix;
+mod tuple;
+mod wide;
+pub use wide::SemVer32;
+mod described;
+pub use described::DescribedVersion;
+mod set;
+pub use set::VersionSet;
+mod compare;
+pub use compare::{earliest, latest, CommitOrdered, UpgradeKind};
+pub mod policy;
+pub use policy::OrderingPolicy;
+pub mod update;
+pub use update::{Decision, UpdatePolicy};
+mod range;
+mod bump;
+mod rollback;
+pub use rollback::{RollbackGuard, UpgradeCheck};
+mod slot;
+pub use slot::{select_boot_slot, Slot, SlotReason};
+mod plan;
+pub use plan::{plan_updates, PlanError, UpdatePlan};
+pub mod manifest;
+pub use manifest::VersionManifest;
+pub mod compat;
+pub use compat::{Compat, CompatMatrix, CompatRule};
+mod hw;
+pub use hw::{DeviceIdent, HwRev};
+pub mod blocklist;
+pub use blocklist::Blocklist;
+mod advisory;
+pub use advisory::Advisory;
+pub mod support;
+pub use support::{SupportStatus, SupportWindow};
+pub mod handshake;
+mod com;
+pub use com::{VersionPacket, COM_MAGIC, COM_PROTOCOL_REV};
+pub mod capabilities;
+pub use capabilities::Capabilities;
+mod changelog;
+pub use changelog::{Changelog, ChangelogEntry};
+pub use range::VersionRange;
+mod assert;
+#[doc(hidden)]
+pub use assert::{__assert_semver_ge, __parse_or_panic};
+mod embed;
+mod build_info;
+mod xous_tag;
+pub use xous_tag::{xous_tag_versions, XousTagVersions, XOUS_TAG_KERNEL, XOUS_TAG_LEN, XOUS_TAG_PROGRAM};
+pub use build_info::BuildInfo;
+pub use embed::{embedded_versions, find_embedded, EmbeddedVersions, EMBED_LEN, EMBED_MAGIC};
+#[doc(hidden)]
+pub use embed::__embed_record;
+#[cfg(feature="base64")]
+pub mod b64;
+#[cfg(feature="ed25519")]
+mod signed;
+#[cfg(feature="ed25519")]
+pub use signed::{SignedVersion, SIGNING_CONTEXT};
+#[cfg(feature="serde")]
+mod serde_support;
+#[cfg(feature="json")]
+mod json;
+#[cfg(feature="workspace")]
+pub mod workspace;
+#[cfg(feature="minicbor")]
+mod cbor;
+#[cfg(feature="rmp")]
+mod msgpack;
+#[cfg(feature="ufmt")]
+mod ufmt_support;
+#[cfg(feature="heapless")]
+mod heapless_support;
+#[cfg(feature="proptest")]
+pub mod strategies;
+#[cfg(feature="semver")]
+mod interop;
+#[cfg(feature="ffi")]
+pub mod ffi;
+#[cfg(feature="wasm")]
+pub mod wasm;
+#[cfg(feature="std")]
+mod git;
+#[cfg(feature="std")]
+pub use git::{source_date_epoch, GitError, GitInfo, GitQuery};
+#[cfg(feature="git2")]
+mod libgit;
+#[cfg(feature="std")]
+mod resolve;
 #[cfg(feature="std")]
-use std::process::Command;
+pub use resolve::{ResolveError, VersionSource};
 #[cfg(feature="std")]
-use std::convert::{From, Into, TryInto};
-use std::cmp::Ordering;
+pub mod build;
+#[cfg(feature="std")]
+pub mod pin;
+#[cfg(feature="std")]
+pub mod graph;
 
-#[derive(Eq, Debug)]
+#[derive(Eq, Debug, Clone, Copy)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature="schemars", derive(schemars::JsonSchema))]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SemVer {
+    // the `semver`-style names other tooling writes are accepted on input; a renamed
+    // field keeps its old name as an alias, so stored records keep deserializing
+    #[cfg_attr(feature="serde", serde(alias = "major"))]
     pub maj: u16,
+    #[cfg_attr(feature="serde", serde(alias = "minor"))]
     pub min: u16,
+    #[cfg_attr(feature="serde", serde(alias = "patch"))]
     pub rev: u16,
+    // fields that were added after the first serialized payloads shipped must carry
+    // `#[serde(default)]`, so that stored records from older firmware keep deserializing.
+    #[cfg_attr(feature="serde", serde(default))]
     pub extra: u16,
+    #[cfg_attr(feature="serde", serde(default, with = "serde_support::commit"))]
+    #[cfg_attr(feature="schemars", schemars(with = "Option<String>", regex(pattern = r"^[0-9a-f]{1,8}$")))]
     pub commit: Option<u32>,
 }
+
+// the text form shared by `SemVer` and `SemVer32`, at whichever component width `T` is:
+// returns `[maj, min, rev, extra]`, the commit, and the number of digits it was written with
+pub(crate) fn parse_components<T: core::str::FromStr + Default>(revstr: &str) -> Result<([T; 4], Option<u32>, u8), ParseError> {
+    // split into at most 5 fields without allocating, so this works without `std`
+    let mut fields = [""; 5];
+    let mut count = 0;
+    for field in revstr.trim_end().strip_prefix('v')
+        .ok_or(ParseError::MissingPrefix)?
+        .split(['.', '-']) {
+        if count == fields.len() {
+            return Err(ParseError::FieldCount);
+        }
+        fields[count] = field;
+        count += 1;
+    }
+    if count < 3 {
+        return Err(ParseError::FieldCount);
+    }
+    let component = |s: &str, name| s.parse::<T>().map_err(|_| ParseError::Component(name));
+    let commit = fields[count - 1].strip_prefix('g').filter(|_| count > 3);
+    let extra = match (count, commit) {
+        (5, _) | (4, None) => component(fields[3], "extra")?,
+        _ => T::default(),
+    };
+    let (commit, commit_digits) = match commit {
+        Some(c) => {
+            // anything past 8 digits is dropped
+            let trunc = c.get(..8).unwrap_or(c);
+            (Some(u32::from_str_radix(trunc, 16).map_err(|_| ParseError::Component("commit"))?), trunc.len() as u8)
+        }
+        None => (None, 0),
+    };
+    let [maj, min, rev] = [fields[0], fields[1], fields[2]];
+    Ok(([component(maj, "maj")?, component(min, "min")?, component(rev, "rev")?, extra], commit, commit_digits))
+}
+
 impl SemVer {
-    #[cfg(feature="std")]
-    pub fn from_git() -> Result<Self, &'static str> {
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .args(["/C", "git describe --tags"])
-                .output()
-                .map_err(|_| "failed to execute process")?
-        } else {
-            Command::new("sh")
-                .arg("-c")
-                .arg("git describe --tags")
-                .output()
-                .map_err(|_| "failed to execute process")?
-        };
-        let gitver = output.stdout;
-        let semver = String::from_utf8_lossy(&gitver);
-        SemVer::from_str(&semver)
+    /// Length of the fixed binary serialization.
+    pub const SERIALIZED_LEN: usize = 16;
+
+    /// A tagged release `maj.min.rev`, with `extra` 0 and no commit. Being `const`, it
+    /// can be used for minimum-version constants.
+    pub const fn new(maj: u16, min: u16, rev: u16) -> Self {
+        SemVer { maj, min, rev, extra: 0, commit: None }
+    }
+    /// `v0.0.0-0` with no commit. Its serialization is all zeros, so it doubles as the
+    /// "nothing programmed" sentinel for blank version fields.
+    pub const ZERO: SemVer = SemVer::new(0, 0, 0);
+
+    pub fn is_zero(&self) -> bool {
+        *self == SemVer::ZERO
+    }
+    /// A tagged release: no commits past the tag (`extra` is 0) and no commit hash.
+    pub fn is_release(&self) -> bool {
+        self.extra == 0 && self.commit.is_none()
+    }
+    /// Anything that isn't a tagged release, e.g. `v0.9.8-760-gabcd1234`.
+    pub fn is_dev(&self) -> bool {
+        !self.is_release()
+    }
+    pub fn has_commit(&self) -> bool {
+        self.commit.is_some()
+    }
+    /// True if a serialized version field has never been written: all zeros (blank) or
+    /// all 0xFF (erased flash).
+    pub fn is_unprogrammed(bytes: &[u8; 16]) -> bool {
+        bytes.iter().all(|&b| b == 0) || bytes.iter().all(|&b| b == 0xff)
+    }
+
+    /// Like `from_str`, but scans a multi-line string and returns the last non-empty
+    /// line that parses as a version.
+    pub fn from_str_last_line(revstr: &str) -> Result<Self, ParseError> {
+        let mut err = ParseError::Empty;
+        for line in revstr.lines().rev().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            match SemVer::from_str(line) {
+                Ok(ver) => return Ok(ver),
+                Err(e) => err = e,
+            }
+        }
+        Err(err)
+    }
+    /// Parses every version in a list separated by whitespace, commas or newlines, such
+    /// as `git tag` output or a manifest field, yielding one result per entry in order.
+    pub fn parse_many(text: &str) -> impl Iterator<Item = Result<SemVer, ParseError>> + '_ {
+        text.split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|entry| !entry.is_empty())
+            .map(SemVer::from_str)
     }
+    /// Parses a Cargo package version such as `0.1.3` (no `v` prefix). Build metadata is
+    /// ignored. Pre-release versions are rejected, as they have no equivalent here.
+    pub fn from_cargo_version(version: &str) -> Result<Self, ParseError> {
+        let core = version.trim().split('+').next().unwrap_or("");
+        if core.contains('-') {
+            return Err(ParseError::FieldCount);
+        }
+        let mut fields = core.split('.');
+        let mut component = |name| fields.next()
+            .and_then(|f| f.parse::<u16>().ok())
+            .ok_or(ParseError::Component(name));
+        let ver = SemVer::new(component("maj")?, component("min")?, component("rev")?);
+        if fields.next().is_some() {
+            return Err(ParseError::FieldCount);
+        }
+        Ok(ver)
+    }
+    /// The `Display` form. Kept inherent, as it predates the `Display` impl.
     #[cfg(feature="std")]
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
-        if let Some(commit) = self.commit {
-            format!("v{}.{}.{}-{}-g{:x}",
-                self.maj, self.min, self.rev, self.extra, commit
-            )
-        } else {
-            format!("v{}.{}.{}-{}", self.maj, self.min, self.rev, self.extra)
-        }
+        format!("{}", self)
     }
-    pub fn from_str(revstr: &str) -> Result<Self, &'static str> {
-        let ver: Vec<&str> = revstr.trim_end().strip_prefix('v')
-            .ok_or("semver does not start with 'v'!")?
-            .split(['.', '-']).collect();
-        if ver.len() != 4 && ver.len() != 5 && ver.len() != 3 {
-            return Err("semver string has wrong number of fields");
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(revstr: &str) -> Result<Self, ParseError> {
+        let ([maj, min, rev, extra], commit, _) = parse_components::<u16>(revstr)?;
+        Ok(SemVer { maj, min, rev, extra, commit })
+    }
+    /// `from_str` for const contexts, e.g. on the output of `env!`. Only trailing ASCII
+    /// whitespace is trimmed.
+    pub const fn from_str_const(revstr: &str) -> Result<Self, ParseError> {
+        let b = revstr.as_bytes();
+        let mut end = b.len();
+        while end > 0 && b[end - 1].is_ascii_whitespace() {
+            end -= 1;
+        }
+        if end == 0 || b[0] != b'v' {
+            return Err(ParseError::MissingPrefix);
         }
-        let extra = if ver.len() == 5 {
-            u16::from_str_radix(ver[3], 10).map_err(|_| "error parsing extra")?
-        } else if ver.len() == 4 {
-            if ver[3].strip_prefix('g').is_some() {
-                0 // last string started with a 'g', it's a commit rev
-            } else { // interpret last string as extra, because no leading 'g'
-                u16::from_str_radix(ver[3], 10).map_err(|_| "error parsing extra")?
+        // (start, end) of each field
+        let mut fields = [(0, 0); 5];
+        let mut count = 0;
+        let mut field_start = 1;
+        let mut i = 1;
+        while i <= end {
+            if i == end || b[i] == b'.' || b[i] == b'-' {
+                if count == fields.len() {
+                    return Err(ParseError::FieldCount);
+                }
+                fields[count] = (field_start, i);
+                count += 1;
+                field_start = i + 1;
             }
-        } else { // must be a length-3 string due to the check above
+            i += 1;
+        }
+        if count < 3 {
+            return Err(ParseError::FieldCount);
+        }
+        let last = fields[count - 1];
+        let has_commit = last.1 > last.0 && b[last.0] == b'g';
+        let extra = if count == 5 || (count == 4 && !has_commit) {
+            match const_decimal(b, fields[3]) {
+                Some(extra) => extra,
+                None => return Err(ParseError::Component("extra")),
+            }
+        } else {
             0
         };
-        Ok(SemVer {
-            maj: u16::from_str_radix(ver[0], 10).map_err(|_| "error parsing maj")?,
-            min: u16::from_str_radix(ver[1], 10).map_err(|_| "error parsing min")?,
-            rev: u16::from_str_radix(ver[2], 10).map_err(|_| "error parsing rev")?,
-            extra,
-            commit: if let Some(c) = ver[ver.len() - 1].strip_prefix('g') {
-                let trunc = if c.len() > 8 { &c[..8] } else { c };
-                Some(u32::from_str_radix(trunc, 16).map_err(|_| "error parsing commit")?)
+        let (Some(maj), Some(min), Some(rev)) = (
+            const_decimal(b, fields[0]), const_decimal(b, fields[1]), const_decimal(b, fields[2])
+        ) else {
+            return Err(if const_decimal(b, fields[0]).is_none() {
+                ParseError::Component("maj")
+            } else if const_decimal(b, fields[1]).is_none() {
+                ParseError::Component("min")
             } else {
-                None
+                ParseError::Component("rev")
+            });
+        };
+        let commit = if count > 3 && has_commit {
+            // like `from_str`, anything past 8 digits is dropped
+            let digits_end = if last.1 - last.0 > 9 { last.0 + 9 } else { last.1 };
+            if digits_end == last.0 + 1 {
+                return Err(ParseError::Component("commit"));
+            }
+            let mut commit = 0u32;
+            let mut i = last.0 + 1;
+            while i < digits_end {
+                let digit = match b[i] {
+                    b'0'..=b'9' => b[i] - b'0',
+                    b'a'..=b'f' => b[i] - b'a' + 10,
+                    b'A'..=b'F' => b[i] - b'A' + 10,
+                    _ => return Err(ParseError::Component("commit")),
+                };
+                commit = (commit << 4) | digit as u32;
+                i += 1;
             }
-        })
+            Some(commit)
+        } else {
+            None
+        };
+        Ok(SemVer { maj, min, rev, extra, commit })
     }
 }
-impl From::<[u8; 16]> for SemVer {
-    fn from(bytes: [u8; 16]) -> SemVer {
+impl SemVer {
+    /// The 16-byte serialization, as a `const fn` so version constants can be baked into
+    /// `static` byte tables. Same as `<[u8; 16]>::from`.
+    pub const fn to_le_bytes(&self) -> [u8; 16] {
+        let [maj0, maj1] = self.maj.to_le_bytes();
+        let [min0, min1] = self.min.to_le_bytes();
+        let [rev0, rev1] = self.rev.to_le_bytes();
+        let [extra0, extra1] = self.extra.to_le_bytes();
         // we use a whole word to store the `Option` flag, just to keep alignment at word alignment.
-        let has_commit = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
+        let (commit, has_commit) = match self.commit {
+            Some(commit) => (commit, 1u32),
+            None => (0, 0),
+        };
+        let [c0, c1, c2, c3] = commit.to_le_bytes();
+        let [f0, f1, f2, f3] = has_commit.to_le_bytes();
+        [maj0, maj1, min0, min1, rev0, rev1, extra0, extra1, c0, c1, c2, c3, f0, f1, f2, f3]
+    }
+    /// Decodes the 16-byte serialization in a `const fn`. Same as `SemVer::from`: any
+    /// nonzero flag word means there is a commit. Use `from_bytes_strict` for untrusted
+    /// storage.
+    pub const fn from_le_bytes(bytes: [u8; 16]) -> SemVer {
+        let [maj0, maj1, min0, min1, rev0, rev1, extra0, extra1, c0, c1, c2, c3, f0, f1, f2, f3] = bytes;
         SemVer {
-            maj: u16::from_le_bytes(bytes[0..2].try_into().unwrap()),
-            min: u16::from_le_bytes(bytes[2..4].try_into().unwrap()),
-            rev: u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
-            extra: u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
-            commit: if has_commit != 0 {Some(u32::from_le_bytes(bytes[8..12].try_into().unwrap()))} else {None},
+            maj: u16::from_le_bytes([maj0, maj1]),
+            min: u16::from_le_bytes([min0, min1]),
+            rev: u16::from_le_bytes([rev0, rev1]),
+            extra: u16::from_le_bytes([extra0, extra1]),
+            commit: if u32::from_le_bytes([f0, f1, f2, f3]) != 0 {
+                Some(u32::from_le_bytes([c0, c1, c2, c3]))
+            } else {
+                None
+            },
         }
     }
+    /// Writes the 16-byte serialization to the start of `buf`, returning the number of
+    /// bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..SemVer::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SemVer::SERIALIZED_LEN })?;
+        dest.copy_from_slice(&<[u8; 16]>::from(self));
+        Ok(SemVer::SERIALIZED_LEN)
+    }
+    /// Reads a 16-byte serialization from the start of `buf`. Trailing bytes are ignored.
+    pub fn read_from(buf: &[u8]) -> Result<SemVer, Error> {
+        let src: &[u8; 16] = buf.get(..SemVer::SERIALIZED_LEN)
+            .and_then(|s| s.try_into().ok())
+            .ok_or(Error::Truncated)?;
+        Ok(SemVer::from(src))
+    }
+    /// Like `From<&[u8; 16]>`, but rejects records that a correct writer could not have
+    /// produced: a flag word other than 0 or 1, or a nonzero commit field when the flag
+    /// says there is no commit. Use this for records read from untrusted storage.
+    pub fn from_bytes_strict(bytes: &[u8; 16]) -> Result<SemVer, Error> {
+        let [.., c0, c1, c2, c3, f0, f1, f2, f3] = *bytes;
+        match u32::from_le_bytes([f0, f1, f2, f3]) {
+            0 => if [c0, c1, c2, c3] != [0u8; 4] {
+                return Err(Error::Malformed("nonzero commit padding"));
+            },
+            1 => {}
+            _ => return Err(Error::Malformed("invalid commit flag")),
+        }
+        Ok(SemVer::from_le_bytes(*bytes))
+    }
+}
+impl From::<[u8; 16]> for SemVer {
+    fn from(bytes: [u8; 16]) -> SemVer {
+        SemVer::from_le_bytes(bytes)
+    }
 }
 impl From::<&[u8; 16]> for SemVer {
     fn from(bytes: &[u8; 16]) -> SemVer {
-        // we use a whole word to store the `Option` flag, just to keep alignment at word alignment.
-        let has_commit = u32::from_le_bytes(bytes[12..16].try_into().unwrap());
-        SemVer {
-            maj: u16::from_le_bytes(bytes[0..2].try_into().unwrap()),
-            min: u16::from_le_bytes(bytes[2..4].try_into().unwrap()),
-            rev: u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
-            extra: u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
-            commit: if has_commit != 0 {Some(u32::from_le_bytes(bytes[8..12].try_into().unwrap()))} else {None},
-        }
+        SemVer::from_le_bytes(*bytes)
+    }
+}
+impl From::<SemVer> for [u8; 16] {
+    fn from(ver: SemVer) -> [u8; 16] {
+        (&ver).into()
+    }
+}
+impl From::<&SemVer> for [u8; 16] {
+    fn from(ver: &SemVer) -> [u8; 16] {
+        ver.to_le_bytes()
     }
 }
-impl Into::<[u8; 16]> for SemVer {
-    fn into(self) -> [u8; 16] {
-        let mut ser = [0u8; 16];
-        ser[0..2].copy_from_slice(&self.maj.to_le_bytes());
-        ser[2..4].copy_from_slice(&self.min.to_le_bytes());
-        ser[4..6].copy_from_slice(&self.rev.to_le_bytes());
-        ser[6..8].copy_from_slice(&self.extra.to_le_bytes());
-        ser[8..12].copy_from_slice(&self.commit.unwrap_or(0).to_le_bytes());
-        ser[12..16].copy_from_slice(&(if self.commit.is_some() {1u32} else {0u32}).to_le_bytes());
-        ser
+/// `vX.Y.Z-extra[-gcommit]`. The alternate form `{:#}` leaves out a zero `extra`, so a
+/// release shows as `v1.2.3`; both forms parse back with `from_str`.
+impl fmt::Display for SemVer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.display_pieces_with(f.alternate(), |piece| f.write_str(piece))
     }
 }
-impl Into::<[u8; 16]> for &SemVer {
-    fn into(self) -> [u8; 16] {
-        let mut ser = [0u8; 16];
-        ser[0..2].copy_from_slice(&self.maj.to_le_bytes());
-        ser[2..4].copy_from_slice(&self.min.to_le_bytes());
-        ser[4..6].copy_from_slice(&self.rev.to_le_bytes());
-        ser[6..8].copy_from_slice(&self.extra.to_le_bytes());
-        ser[8..12].copy_from_slice(&self.commit.unwrap_or(0).to_le_bytes());
-        ser[12..16].copy_from_slice(&(if self.commit.is_some() {1u32} else {0u32}).to_le_bytes());
-        ser
+impl core::str::FromStr for SemVer {
+    type Err = ParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SemVer::from_str(s)
     }
 }
 impl Ord for SemVer {
     fn cmp(&self, other: &Self) -> Ordering {
-        // we can just concatenate all the fields together and do a numerical comparison.
+        // we can just concatenate all the fields together and do a numerical comparison,
+        // which is exactly the 64-bit register packing.
         // commits are extra metadata in the record, and have no meaning in a comparison
-        let mine: u64 =
-            (self.maj as u64) << 48
-            | (self.min as u64) << 32
-            | (self.rev as u64) << 16
-            | (self.extra as u64);
-        let theirs: u64 =
-            (other.maj as u64) << 48
-            | (other.min as u64) << 32
-            | (other.rev as u64) << 16
-            | (other.extra as u64);
-        mine.cmp(&theirs)
+        u64::from(self).cmp(&u64::from(other))
     }
 }
 impl PartialOrd for SemVer {
@@ -145,8 +446,15 @@ impl PartialOrd for SemVer {
         Some(self.cmp(other))
     }
 }
+impl Default for SemVer {
+    fn default() -> Self {
+        SemVer::ZERO
+    }
+}
+/// `==` is `eq_exact`: it also considers the commit, so `v0.9.8` built with `--long`
+/// (`v0.9.8-0-gabcd1234`) is not equal to plain `v0.9.8`. Use `eq_release` to ignore
+/// the commit, consistently with `Ord`.
 impl PartialEq for SemVer {
-    // NOTE: equality also considers the commit rev
     fn eq(&self, other: &Self) -> bool {
         self.maj == other.maj
         && self.min == other.min
@@ -155,17 +463,98 @@ impl PartialEq for SemVer {
         && self.commit == other.commit
     }
 }
+// comparisons against version strings parse the string on the fly. Strings that don't
+// parse are never equal to a version, and are unordered with respect to it.
+impl PartialEq<str> for SemVer {
+    fn eq(&self, other: &str) -> bool {
+        SemVer::from_str(other).is_ok_and(|o| *self == o)
+    }
+}
+impl PartialEq<&str> for SemVer {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+impl PartialOrd<str> for SemVer {
+    fn partial_cmp(&self, other: &str) -> Option<Ordering> {
+        SemVer::from_str(other).ok().map(|o| self.cmp(&o))
+    }
+}
+impl PartialOrd<&str> for SemVer {
+    fn partial_cmp(&self, other: &&str) -> Option<Ordering> {
+        self.partial_cmp(*other)
+    }
+}
+const fn const_decimal(b: &[u8], (start, end): (usize, usize)) -> Option<u16> {
+    if start == end {
+        return None;
+    }
+    let mut value: u16 = 0;
+    let mut i = start;
+    while i < end {
+        if !b[i].is_ascii_digit() {
+            return None;
+        }
+        value = match value.checked_mul(10) {
+            Some(v) => match v.checked_add((b[i] - b'0') as u16) {
+                Some(v) => v,
+                None => return None,
+            },
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(value)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature="serde")]
     #[test]
-    fn test_gitver() {
-        let gitver = SemVer::from_git();
-        println!("{:?}", gitver);
-        assert!(gitver.is_ok());
+    fn test_serde_generations() {
+        let current = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let json = serde_json::to_string(&current).unwrap();
+        assert_eq!(json, r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"abcd1234"}"#);
+        assert_eq!(serde_json::from_str::<SemVer>(&json).unwrap(), current);
+        // each entry is a payload as written by an older (or newer) generation of the crate
+        let matrix = [
+            (r#"{"maj":0,"min":9,"rev":8}"#, "v0.9.8"),
+            (r#"{"maj":0,"min":9,"rev":8,"extra":760}"#, "v0.9.8-760"),
+            (r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":null}"#, "v0.9.8-760"),
+            // commits were written as integers before the canonical JSON form
+            (r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":2882343476}"#, "v0.9.8-760-gabcd1234"),
+            (r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"abcd1234"}"#, "v0.9.8-760-gabcd1234"),
+            // fields from a newer writer are ignored
+            (r#"{"maj":0,"min":9,"rev":8,"extra":760,"commit":"abcd1234","dirty":true}"#, "v0.9.8-760-gabcd1234"),
+            (r#"{"maj":0,"min":9,"rev":8,"epoch":1700000000,"channel":"beta"}"#, "v0.9.8"),
+            // `semver`-style names
+            (r#"{"major":0,"minor":9,"patch":8}"#, "v0.9.8"),
+            (r#"{"major":0,"minor":9,"patch":8,"extra":760,"commit":"abcd1234"}"#, "v0.9.8-760-gabcd1234"),
+        ];
+        for (payload, expected) in matrix {
+            assert_eq!(
+                serde_json::from_str::<SemVer>(payload).unwrap(),
+                SemVer::from_str(expected).unwrap(),
+                "payload {}", payload
+            );
+            assert_eq!(
+                serde_json::from_str::<SemVer32>(payload).unwrap(),
+                expected.parse::<SemVer32>().unwrap(),
+                "payload {}", payload
+            );
+        }
+        assert!(serde_json::from_str::<SemVer>(r#"{"maj":0,"min":9}"#).is_err());
+        // a field given under both names is a conflict, not a silent pick
+        assert!(serde_json::from_str::<SemVer>(r#"{"maj":0,"major":1,"min":9,"rev":8}"#).is_err());
+        // written with the current names only, which older readers know
+        assert_eq!(serde_json::to_string(&SemVer32::from(current)).unwrap(), json);
     }
     #[test]
     fn test_strver() {
+        // only trailing whitespace, such as the newline from `git describe`, is trimmed
+        assert_eq!(SemVer::from_str("v1.2.3\n"), Ok(SemVer::new(1, 2, 3)));
+        assert_eq!(SemVer::from_str(" v1.2.3"), Err(ParseError::MissingPrefix));
         assert_eq!(
             SemVer::from_str("v0.9.8-760-gabcd1234"),
             Ok(SemVer {
@@ -310,6 +699,46 @@ mod tests {
         assert_eq!(SemVer::from_str("v0.9.8-760").unwrap(),
             SemVer::from(&bytes)
         );
+        let bytes: [u8; 16] = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap().into();
+        assert_eq!(SemVer::from_bytes_strict(&bytes).unwrap(), SemVer::from(&bytes));
+        let bytes: [u8; 16] = SemVer::from_str("v0.9.8-760").unwrap().into();
+        assert_eq!(SemVer::from_bytes_strict(&bytes).unwrap(), SemVer::from(&bytes));
+        let bytes = [0, 0,
+        9, 0,
+        8, 0,
+        248, 2,
+        0x34, 0x12, 0xcd, 0xab,
+        0x00, 0, 0, 0
+        ];
+        assert_eq!(SemVer::from_bytes_strict(&bytes), Err(Error::Malformed("nonzero commit padding")));
+        let bytes = [0, 0,
+        9, 0,
+        8, 0,
+        248, 2,
+        0x34, 0x12, 0xcd, 0xab,
+        0x01, 0, 0, 0x80
+        ];
+        assert_eq!(SemVer::from_bytes_strict(&bytes), Err(Error::Malformed("invalid commit flag")));
+        assert_eq!(SemVer::from_bytes_strict(&[0xff; 16]), Err(Error::Malformed("invalid commit flag")));
+        let mut buf = [0xffu8; 20];
+        assert_eq!(SemVer::from_str("v0.9.8-760-gabcd1234").unwrap().write_to(&mut buf), Ok(16));
+        assert_eq!(
+            buf,
+            [0, 0,
+            9, 0,
+            8, 0,
+            248, 2,
+            0x34, 0x12, 0xcd, 0xab,
+            0x01, 0, 0, 0,
+            0xff, 0xff, 0xff, 0xff
+            ]
+        );
+        assert_eq!(SemVer::read_from(&buf).unwrap(), SemVer::from_str("v0.9.8-760-gabcd1234").unwrap());
+        assert_eq!(SemVer::read_from(&buf[..15]), Err(Error::Truncated));
+        assert_eq!(
+            SemVer::from_str("v0.9.8").unwrap().write_to(&mut buf[..15]),
+            Err(Error::BufferTooSmall { needed: 16 })
+        );
         assert_eq!(SemVer {
             maj: 0, min: 9, rev: 8, extra: 42, commit: None
         }.to_string(),
@@ -321,4 +750,186 @@ mod tests {
             "v0.9.8-42-g123abc".to_string()
         );
     }
+    #[test]
+    fn test_parse_many() {
+        let mut vers = SemVer::parse_many("v0.9.8, v0.9.9-3\n\tv1.0.0-0-gabcd1234,,bogus\n");
+        assert_eq!(vers.next(), Some(Ok(SemVer::new(0, 9, 8))));
+        assert_eq!(vers.next(), Some(SemVer::from_str("v0.9.9-3")));
+        assert_eq!(vers.next(), Some(SemVer::from_str("v1.0.0-0-gabcd1234")));
+        assert_eq!(vers.next(), Some(Err(ParseError::MissingPrefix)));
+        assert_eq!(vers.next(), None);
+        assert_eq!(SemVer::parse_many(" \n, ").count(), 0);
+    }
+    #[test]
+    fn test_str_const() {
+        for s in [
+            "v0.9.8-760-gabcd1234", "v0.9.8-760", "v0.9.8-gabcd1234", "v0.9.8", "v1.2.3\n", " v1.2.3",
+            "v0.9.8-760-gabcd12345678", "v0.9.8-760-x", "v0.9.8-760-g", "v0.9.8-760-gxyz",
+            "v0.9", "v0.9.8-1-2-3", "0.9.8", "", "v", "v65536.0.0", "v0.x.0", "v0.0.y", "v0.0.0-z",
+            "v0..0", "v0.9.8-760-gABCD",
+        ] {
+            assert_eq!(SemVer::from_str_const(s), SemVer::from_str(s), "{:?}", s);
+        }
+        const VERSION: SemVer = match SemVer::from_str_const("v0.9.8-760-gabcd1234") {
+            Ok(v) => v,
+            Err(_) => panic!(),
+        };
+        assert_eq!(VERSION.commit, Some(0xabcd1234));
+    }
+    // there's no `no_panic` crate in our builds, so instead hammer the paths the loader
+    // relies on with edge cases and pseudo-random records
+    #[test]
+    fn test_conversions_never_panic() {
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut records = vec![[0u8; 16], [0xff; 16]];
+        for _ in 0..20_000 {
+            let mut record = [0u8; 16];
+            for chunk in record.chunks_exact_mut(8) {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                chunk.copy_from_slice(&state.to_le_bytes());
+            }
+            records.push(record);
+            record[12..16].copy_from_slice(&[1, 0, 0, 0]);
+            records.push(record);
+        }
+        let mut buf = [0u8; MAX_DISPLAY_LEN];
+        for record in &records {
+            let ver = SemVer::from(record);
+            let strict = SemVer::from_bytes_strict(record);
+            if let Ok(strict) = strict {
+                assert_eq!(strict.to_le_bytes(), *record);
+            }
+            let len = ver.write_display(&mut buf).unwrap();
+            assert_eq!(&buf[..len], ver.to_string().as_bytes());
+            assert_eq!(&*ver.to_display_buf(), ver.to_string());
+            for short in 0..len {
+                assert_eq!(ver.write_display(&mut buf[..short]), Err(Error::BufferTooSmall { needed: len }));
+            }
+        }
+    }
+    #[test]
+    #[cfg(feature="arbitrary")]
+    fn test_arbitrary() {
+        use arbitrary::{Arbitrary, Unstructured};
+        let data: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        let mut buf = [0u8; 16];
+        while !u.is_empty() {
+            let ver = SemVer::arbitrary(&mut u).unwrap();
+            ver.write_to(&mut buf).unwrap();
+            assert!(SemVer::from_bytes_strict(&buf).unwrap().eq_exact(&ver));
+            assert!(SemVer::from_str(&ver.to_string()).unwrap().eq_exact(&ver));
+            let _ = VersionManifest::arbitrary(&mut u);
+        }
+    }
+    #[test]
+    fn test_alternate_display() {
+        for (s, short) in [
+            ("v1.2.3", "v1.2.3"),
+            ("v0.9.8-760", "v0.9.8-760"),
+            ("v0.9.8-760-gabcd1234", "v0.9.8-760-gabcd1234"),
+            ("v0.9.8-0-gabcd1234", "v0.9.8-gabcd1234"),
+        ] {
+            let ver = SemVer::from_str(s).unwrap();
+            assert_eq!(format!("{:#}", ver), short);
+            assert!(SemVer::from_str(short).unwrap().eq_exact(&ver));
+        }
+        assert_eq!(SemVer::new(1, 2, 3).to_string(), "v1.2.3-0");
+    }
+    #[test]
+    fn test_le_bytes_const() {
+        const VERSION: SemVer = SemVer { maj: 0, min: 9, rev: 8, extra: 760, commit: Some(0xabcd1234) };
+        static TABLE: [[u8; 16]; 2] = [VERSION.to_le_bytes(), SemVer::new(1, 2, 3).to_le_bytes()];
+        const ROUND_TRIP: SemVer = SemVer::from_le_bytes(VERSION.to_le_bytes());
+        assert_eq!(TABLE[0], <[u8; 16]>::from(&VERSION));
+        assert_eq!(TABLE[1], [1, 0, 2, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(ROUND_TRIP, VERSION);
+        let mut lenient = TABLE[1];
+        lenient[12] = 2;
+        assert_eq!(SemVer::from_le_bytes(lenient), SemVer::from(&lenient));
+        assert_eq!(SemVer::from_le_bytes(lenient).commit, Some(0));
+    }
+    #[test]
+    fn test_assert_ge() {
+        const FLOOR: SemVer = SemVer::new(0, 9, 10);
+        assert_semver_ge!(FLOOR, "v0.9.10");
+        assert_semver_ge!(SemVer::new(1, 0, 0), "v0.9.10-5");
+        assert_semver_ge!("v0.9.10-6-gabcd1234", "v0.9.10-5");
+        __assert_semver_ge(SemVer::new(0, 10, 0), FLOOR);
+        assert!(std::panic::catch_unwind(|| __assert_semver_ge(SemVer::new(0, 9, 9), FLOOR)).is_err());
+        assert!(std::panic::catch_unwind(|| __parse_or_panic("0.9.9")).is_err());
+    }
+    #[test]
+    fn test_release_predicates() {
+        let release = SemVer::new(0, 9, 8);
+        assert!(release.is_release() && !release.is_dev() && !release.has_commit());
+        let dev = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert!(!dev.is_release() && dev.is_dev() && dev.has_commit());
+        let ahead = SemVer::from_str("v0.9.8-760").unwrap();
+        assert!(ahead.is_dev() && !ahead.has_commit());
+        // a tag built from its own commit still records the hash
+        let tagged = SemVer::from_str("v0.9.8-gabcd1234").unwrap();
+        assert!(tagged.is_dev() && tagged.has_commit());
+    }
+    #[test]
+    fn test_cargo_version() {
+        assert_eq!(SemVer::from_cargo_version("0.1.3"), Ok(SemVer::new(0, 1, 3)));
+        assert_eq!(SemVer::from_cargo_version("10.20.30+build.5\n"), Ok(SemVer::new(10, 20, 30)));
+        assert_eq!(SemVer::from_cargo_version("0.1.3-rc.1"), Err(ParseError::FieldCount));
+        assert_eq!(SemVer::from_cargo_version("0.1"), Err(ParseError::Component("rev")));
+        assert_eq!(SemVer::from_cargo_version("0.1.3.4"), Err(ParseError::FieldCount));
+        assert_eq!(SemVer::from_cargo_version("v0.1.3"), Err(ParseError::Component("maj")));
+    }
+    #[test]
+    fn test_str_cmp() {
+        let ver = SemVer::from_str("v0.9.8-760").unwrap();
+        assert!(ver == "v0.9.8-760");
+        assert!(ver != "v0.9.8-760-gabcd1234");
+        assert!(ver != "not a version");
+        assert!(ver >= "v0.9.8");
+        assert!(ver < "v1.0.0");
+        assert!(ver > "v0.9.8-759-g1234");
+        assert_eq!(ver.partial_cmp("garbage"), None);
+    }
+    #[test]
+    fn test_zero() {
+        assert_eq!(SemVer::default(), SemVer::ZERO);
+        assert!(SemVer::default().is_zero());
+        assert!(SemVer::from_str("v0.0.0").unwrap().is_zero());
+        assert!(!SemVer::from_str("v0.0.0-0-g1").unwrap().is_zero());
+        assert!(!SemVer::from_str("v0.0.1").unwrap().is_zero());
+        assert_eq!(<[u8; 16]>::from(SemVer::ZERO), [0u8; 16]);
+        assert!(SemVer::is_unprogrammed(&[0u8; 16]));
+        assert!(SemVer::is_unprogrammed(&[0xffu8; 16]));
+        assert!(!SemVer::is_unprogrammed(&SemVer::new(0, 9, 8).into()));
+    }
+    #[cfg(feature="std")]
+    #[test]
+    fn test_error_trait() {
+        fn parse(s: &str) -> Result<SemVer, Box<dyn std::error::Error>> {
+            Ok(SemVer::from_str(s)?)
+        }
+        fn decode(b: &[u8]) -> Result<SemVer, Box<dyn std::error::Error>> {
+            Ok(SemVer::read_from(b)?)
+        }
+        assert_eq!(parse("1.2.3").unwrap_err().to_string(), "semver does not start with 'v'!");
+        assert_eq!(parse("v1.2.x").unwrap_err().to_string(), "error parsing rev");
+        assert_eq!(decode(&[0; 4]).unwrap_err().to_string(), "data is truncated");
+        let err = SemVer::from_git_output("fatal: no tags\n").unwrap_err();
+        assert_eq!(
+            std::error::Error::source(&err).unwrap().to_string(),
+            "semver does not start with 'v'!"
+        );
+    }
+    #[cfg(feature="schemars")]
+    #[test]
+    fn test_json_schema() {
+        let schema = serde_json::to_value(schemars::schema_for!(SemVer)).unwrap();
+        assert_eq!(schema["required"], serde_json::json!(["maj", "min", "rev"]));
+        assert_eq!(schema["properties"]["commit"]["pattern"], "^[0-9a-f]{1,8}$");
+        assert_eq!(schema["properties"]["commit"]["type"], serde_json::json!(["string", "null"]));
+        assert_eq!(schema["properties"]["maj"]["format"], "uint16");
+    }
 }
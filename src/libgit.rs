@@ -0,0 +1,262 @@
+//! `git describe --tags --dirty` reimplemented on libgit2, producing the same output text
+//! so it can go through the same parser as the subprocess backend.
+//!
+//! The tag and commit count follow git's `describe.c` step for step, since its count is
+//! a heuristic rather than an exact `rev-list --count`: history is walked newest commit
+//! first, collecting up to `MAX_CANDIDATES` tagged commits while counting the commits
+//! each one doesn't reach, and the candidate with the lowest count wins. On a commit
+//! with several tags, annotated tags beat lightweight ones, the newer annotated tag
+//! wins, and otherwise the first name in ref order.
+use std::collections::{HashMap, VecDeque};
+use git2::{Commit, ErrorCode, Oid, Repository, StatusOptions};
+use crate::{GitError, GitQuery};
+
+fn lib_err(e: git2::Error) -> GitError {
+    GitError::Library(e.message().to_string())
+}
+
+fn open(query: &GitQuery) -> Result<Repository, GitError> {
+    Repository::discover(query.dir.as_deref().unwrap_or(".".as_ref())).map_err(lib_err)
+}
+
+pub(crate) fn branch(query: &GitQuery) -> Result<Option<String>, GitError> {
+    let repo = open(query)?;
+    let head = repo.head().map_err(lib_err)?;
+    Ok(if head.is_branch() { head.shorthand().map(String::from) } else { None })
+}
+
+pub(crate) fn timestamp(query: &GitQuery) -> Result<Option<u64>, GitError> {
+    let repo = open(query)?;
+    let head = repo.head().map_err(lib_err)?.peel_to_commit().map_err(lib_err)?;
+    let seconds = head.committer().when().seconds();
+    Ok(u64::try_from(seconds).ok())
+}
+
+// git's default `--candidates`
+const MAX_CANDIDATES: usize = 10;
+
+// marks a commit as queued; each candidate gets a bit above it
+const SEEN: u32 = 1;
+
+// a tag name with git's preference: annotated (2) over lightweight (1), then tagger date
+struct Name {
+    name: String,
+    prio: u8,
+    date: i64,
+}
+
+pub(crate) fn describe(query: &GitQuery) -> Result<String, GitError> {
+    let repo = open(query)?;
+    let head = repo.head().map_err(lib_err)?.peel_to_commit().map_err(lib_err)?;
+    let names = names(&repo, query)?;
+
+    let (name, depth) = match names.get(&head.id()) {
+        Some(exact) => (exact.name.as_str(), 0),
+        None => nearest(&repo, &head, &names)?,
+    };
+
+    let mut out = name.to_string();
+    if depth > 0 {
+        out.push_str(&format!("-{}-g{}", depth, abbrev(&repo, head.id())?));
+    }
+    let mut opts = StatusOptions::new();
+    opts.include_untracked(false).include_ignored(false);
+    if !repo.statuses(Some(&mut opts)).map_err(lib_err)?.is_empty() {
+        out.push_str("-dirty");
+    }
+    out.push('\n');
+    Ok(out)
+}
+
+// the tags `query` selects, one per commit
+fn names(repo: &Repository, query: &GitQuery) -> Result<HashMap<Oid, Name>, GitError> {
+    let mut excluded = Vec::new();
+    for pattern in &query.excluding {
+        excluded.extend(repo.tag_names(Some(pattern)).map_err(lib_err)?.iter().flatten().map(String::from));
+    }
+    let mut names = Vec::new();
+    if query.matching.is_empty() {
+        names.extend(repo.tag_names(None).map_err(lib_err)?.iter().flatten().map(String::from));
+    }
+    for pattern in &query.matching {
+        names.extend(repo.tag_names(Some(pattern)).map_err(lib_err)?.iter().flatten().map(String::from));
+    }
+    // in ref order, so that among equals the first name stays
+    names.sort();
+    names.dedup();
+    let mut tags: HashMap<Oid, Name> = HashMap::new();
+    for name in names.into_iter().filter(|n| !excluded.contains(n)) {
+        let obj = repo.revparse_single(&format!("refs/tags/{}", name)).map_err(lib_err)?;
+        // git skips tags of trees and blobs
+        let Ok(target) = obj.peel_to_commit() else { continue };
+        let (prio, date) = match obj.as_tag() {
+            Some(tag) => (2, tag.tagger().map_or(0, |t| t.when().seconds())),
+            None => (1, 0),
+        };
+        let replace = tags.get(&target.id())
+            .is_none_or(|e| e.prio < prio || (prio == 2 && e.prio == 2 && e.date < date));
+        if replace {
+            tags.insert(target.id(), Name { name, prio, date });
+        }
+    }
+    Ok(tags)
+}
+
+// a tagged commit met on the walk, with the bit marking the commits it reaches
+struct Candidate<'a> {
+    name: &'a str,
+    depth: usize,
+    flag: u32,
+}
+
+// git's `describe_commit`: the best tag below `head` and its commit count
+fn nearest<'a>(
+    repo: &Repository,
+    head: &Commit,
+    names: &'a HashMap<Oid, Name>,
+) -> Result<(&'a str, usize), GitError> {
+    let mut flags = HashMap::from([(head.id(), SEEN)]);
+    let mut list = VecDeque::from([(head.id(), head.time().seconds())]);
+    let mut candidates: Vec<Candidate> = Vec::new();
+    let (mut seen, mut annotated, mut gave_up_on) = (0, false, None);
+    while let Some((oid, date)) = list.pop_front() {
+        seen += 1;
+        if let Some(name) = names.get(&oid) {
+            if candidates.len() == MAX_CANDIDATES {
+                gave_up_on = Some((oid, date));
+                break;
+            }
+            let flag = 1 << (candidates.len() + 1);
+            candidates.push(Candidate { name: &name.name, depth: seen - 1, flag });
+            *flags.entry(oid).or_default() |= flag;
+            annotated |= name.prio == 2;
+        }
+        let within = flags.get(&oid).copied().unwrap_or_default();
+        for candidate in candidates.iter_mut().filter(|c| within & c.flag == 0) {
+            candidate.depth += 1;
+        }
+        if annotated && list.is_empty() {
+            break;
+        }
+        queue_parents(repo, oid, within, &mut flags, &mut list)?;
+    }
+    // stable, so equal counts keep the order they were found in
+    candidates.sort_by_key(|c| c.depth);
+    let best = candidates.first_mut().ok_or(GitError::NoTags)?;
+    if let Some((oid, date)) = gave_up_on {
+        insert_by_date(&mut list, oid, date);
+    }
+    // finish counting for the winner, until everything left is below it
+    while let Some((oid, _)) = list.pop_front() {
+        let within = flags.get(&oid).copied().unwrap_or_default();
+        if within & best.flag == 0 {
+            best.depth += 1;
+        } else if list.iter().all(|(o, _)| flags.get(o).is_some_and(|f| f & best.flag != 0)) {
+            break;
+        }
+        queue_parents(repo, oid, within, &mut flags, &mut list)?;
+    }
+    Ok((best.name, best.depth))
+}
+
+// queues the parents not yet seen, and passes `within` down to all of them
+fn queue_parents(
+    repo: &Repository,
+    oid: Oid,
+    within: u32,
+    flags: &mut HashMap<Oid, u32>,
+    list: &mut VecDeque<(Oid, i64)>,
+) -> Result<(), GitError> {
+    for parent in repo.find_commit(oid).map_err(lib_err)?.parents() {
+        let parent_flags = flags.entry(parent.id()).or_default();
+        if *parent_flags & SEEN == 0 {
+            insert_by_date(list, parent.id(), parent.time().seconds());
+        }
+        *parent_flags |= within;
+    }
+    Ok(())
+}
+
+// newest first, and after any commits of the same date, as `commit_list_insert_by_date`
+fn insert_by_date(list: &mut VecDeque<(Oid, i64)>, oid: Oid, date: i64) {
+    let at = list.iter().position(|&(_, d)| d < date).unwrap_or(list.len());
+    list.insert(at, (oid, date));
+}
+
+// `--abbrev=8`: at least 8 digits, and more while that prefix is ambiguous
+fn abbrev(repo: &Repository, oid: Oid) -> Result<String, GitError> {
+    let odb = repo.odb().map_err(lib_err)?;
+    let hex = oid.to_string();
+    for len in 8..hex.len() {
+        match odb.exists_prefix(oid, len) {
+            Ok(_) => return Ok(hex[..len].to_string()),
+            Err(e) if e.code() == ErrorCode::Ambiguous => continue,
+            Err(e) => return Err(lib_err(e)),
+        }
+    }
+    Ok(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::git::tests::{git, scratch_repo};
+    use crate::{GitError, GitQuery, SemVer};
+
+    #[test]
+    fn test_describe_git2() {
+        let repo = scratch_repo("libgit");
+        assert_eq!(GitQuery::new().dir(&repo).describe_git2(), Err(GitError::NoTags));
+        git(&repo, &["tag", "v1.2.3"]);
+        git(&repo, &["tag", "-a", "-m", "soc", "soc-v0.4.0"]);
+        std::fs::write(repo.join("file"), "two").unwrap();
+        git(&repo, &["commit", "-q", "-am", "two"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "three"]);
+        std::fs::write(repo.join("file"), "four").unwrap();
+        let queries = [
+            GitQuery::new().dir(&repo).matching("v*"),
+            GitQuery::new().dir(&repo).matching("soc-v*"),
+            GitQuery::new().dir(&repo).matching("*v*").excluding("soc-*"),
+        ];
+        for query in queries {
+            let lib = query.describe_git2().unwrap();
+            assert_eq!(lib, query.describe().unwrap());
+            assert!(lib.dirty);
+            assert_eq!(lib.version.extra, 2);
+        }
+        assert_eq!(GitQuery::new().dir(&repo).matching("v*").describe_git2().unwrap().version.maj, 1);
+        git(&repo, &["checkout", "-q", "--detach"]);
+        let query = GitQuery::new().dir(&repo).matching("v*");
+        assert_eq!(query.describe_git2().unwrap(), query.describe().unwrap());
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+    #[test]
+    fn test_describe_git2_parity() {
+        let repo = scratch_repo("libgit-parity");
+        git(&repo, &["tag", "v1.0.0"]);
+        git(&repo, &["checkout", "-q", "-b", "side"]);
+        for i in 0..5 {
+            git(&repo, &["commit", "-q", "--allow-empty", "-m", &format!("side {}", i)]);
+        }
+        git(&repo, &["tag", "v1.1.0"]);
+        git(&repo, &["checkout", "-q", "-"]);
+        git(&repo, &["commit", "-q", "--allow-empty", "-m", "main"]);
+        git(&repo, &["tag", "v2.0.0"]);
+        git(&repo, &["merge", "-q", "--no-ff", "-m", "merge", "side"]);
+        let query = GitQuery::new().dir(&repo);
+        // v1.1.0 is closer through the merge than v2.0.0 on the first parent; the count
+        // itself is git's heuristic, which shifts when commits share a timestamp
+        let lib = query.describe_git2().unwrap();
+        assert_eq!(lib, query.describe().unwrap());
+        assert_eq!((lib.version.maj, lib.version.min), (1, 1));
+
+        // on one commit, an annotated tag beats lightweight ones, which go in ref order
+        git(&repo, &["tag", "v3.0.1"]);
+        git(&repo, &["tag", "v3.0.0"]);
+        assert_eq!(query.describe_git2().unwrap(), query.describe().unwrap());
+        assert_eq!(query.describe_git2().unwrap().version, SemVer::new(3, 0, 0));
+        git(&repo, &["tag", "-a", "-m", "release", "v3.0.2"]);
+        assert_eq!(query.describe_git2().unwrap(), query.describe().unwrap());
+        assert_eq!(query.describe_git2().unwrap().version, SemVer::new(3, 0, 2));
+        std::fs::remove_dir_all(&repo).unwrap();
+    }
+}
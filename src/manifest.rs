@@ -0,0 +1,169 @@
+//! The versions of every independently-updated part of a Betrusted device.
+//!
+//! The binary form is the 16-byte serialization of each component in `Component::ALL`
+//! order, 96 bytes in total. A missing component is written as all zeros; all zeros or
+//! all 0xFF (erased flash) read back as missing.
+use core::fmt;
+use crate::{Error, MaybeVersion, SemVer, UpgradeKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Component {
+    /// SoC gateware
+    Soc,
+    /// EC firmware
+    Ec,
+    /// WF200 wifi firmware
+    Wf200,
+    Loader,
+    Kernel,
+    Apps,
+}
+
+impl Component {
+    /// Every component, in serialization order.
+    pub const ALL: [Component; 6] = [
+        Component::Soc, Component::Ec, Component::Wf200,
+        Component::Loader, Component::Kernel, Component::Apps,
+    ];
+    pub fn name(&self) -> &'static str {
+        match self {
+            Component::Soc => "soc",
+            Component::Ec => "ec",
+            Component::Wf200 => "wf200",
+            Component::Loader => "loader",
+            Component::Kernel => "kernel",
+            Component::Apps => "apps",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub struct VersionManifest {
+    pub soc: Option<SemVer>,
+    pub ec: Option<SemVer>,
+    pub wf200: Option<SemVer>,
+    pub loader: Option<SemVer>,
+    pub kernel: Option<SemVer>,
+    pub apps: Option<SemVer>,
+}
+
+impl VersionManifest {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = Component::ALL.len() * SemVer::SERIALIZED_LEN;
+
+    pub fn get(&self, component: Component) -> Option<SemVer> {
+        *self.slot(component)
+    }
+    pub fn set(&mut self, component: Component, version: Option<SemVer>) {
+        *self.slot_mut(component) = version;
+    }
+    fn slot(&self, component: Component) -> &Option<SemVer> {
+        match component {
+            Component::Soc => &self.soc,
+            Component::Ec => &self.ec,
+            Component::Wf200 => &self.wf200,
+            Component::Loader => &self.loader,
+            Component::Kernel => &self.kernel,
+            Component::Apps => &self.apps,
+        }
+    }
+    fn slot_mut(&mut self, component: Component) -> &mut Option<SemVer> {
+        match component {
+            Component::Soc => &mut self.soc,
+            Component::Ec => &mut self.ec,
+            Component::Wf200 => &mut self.wf200,
+            Component::Loader => &mut self.loader,
+            Component::Kernel => &mut self.kernel,
+            Component::Apps => &mut self.apps,
+        }
+    }
+    /// For each component, what moving from `self` to `to` means, or `None` where
+    /// either side is missing.
+    pub fn delta(&self, to: &VersionManifest) -> [(Component, Option<UpgradeKind>); 6] {
+        Component::ALL.map(|c| {
+            let kind = match (self.get(c), to.get(c)) {
+                (Some(from), Some(to)) => Some(from.delta(&to)),
+                _ => None,
+            };
+            (c, kind)
+        })
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    /// Fails as `MaybeVersion::write_to` does if a component is `v0.0.0-0`.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..VersionManifest::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: VersionManifest::SERIALIZED_LEN })?;
+        for (c, chunk) in Component::ALL.iter().zip(dest.chunks_exact_mut(SemVer::SERIALIZED_LEN)) {
+            MaybeVersion(self.get(*c)).write_to(chunk)?;
+        }
+        Ok(VersionManifest::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<VersionManifest, Error> {
+        let src = buf.get(..VersionManifest::SERIALIZED_LEN).ok_or(Error::Truncated)?;
+        let mut manifest = VersionManifest::default();
+        for (c, chunk) in Component::ALL.iter().zip(src.chunks_exact(SemVer::SERIALIZED_LEN)) {
+            manifest.set(*c, MaybeVersion::read_from(chunk)?.0);
+        }
+        Ok(manifest)
+    }
+}
+
+/// One `name: version` line per component, with `-` for missing ones.
+impl fmt::Display for VersionManifest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, c) in Component::ALL.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match self.get(*c) {
+                Some(ver) => write!(f, "{}: {}", c.name(), ver)?,
+                None => write!(f, "{}: -", c.name())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_manifest() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let mut manifest = VersionManifest {
+            soc: Some(v("v0.9.8-760-gabcd1234")),
+            ec: Some(v("v0.9.8")),
+            loader: Some(v("v0.9.7-12")),
+            ..Default::default()
+        };
+        manifest.set(Component::Kernel, Some(v("v0.9.8-761")));
+        assert_eq!(manifest.get(Component::Kernel), Some(v("v0.9.8-761")));
+        assert_eq!(
+            manifest.to_string(),
+            "soc: v0.9.8-760-gabcd1234\nec: v0.9.8-0\nwf200: -\nloader: v0.9.7-12\nkernel: v0.9.8-761\napps: -"
+        );
+
+        let mut buf = [0xffu8; 100];
+        assert_eq!(manifest.write_to(&mut buf), Ok(96));
+        assert_eq!(buf[32..48], [0u8; 16]);
+        assert_eq!(VersionManifest::read_from(&buf), Ok(manifest));
+        buf[80..96].fill(0xff);
+        assert_eq!(VersionManifest::read_from(&buf).unwrap().apps, None);
+        assert_eq!(VersionManifest::read_from(&buf[..95]), Err(Error::Truncated));
+        assert_eq!(manifest.write_to(&mut buf[..95]), Err(Error::BufferTooSmall { needed: 96 }));
+
+        let mut update = manifest;
+        update.ec = Some(v("v0.10.0"));
+        update.loader = Some(v("v0.9.6"));
+        update.wf200 = Some(v("v3.12.1"));
+        let delta = update.delta(&manifest);
+        assert_eq!(delta[0], (Component::Soc, Some(UpgradeKind::Identical)));
+        assert_eq!(delta[1], (Component::Ec, Some(UpgradeKind::Downgrade)));
+        assert_eq!(delta[2], (Component::Wf200, None));
+        assert_eq!(manifest.delta(&update)[3], (Component::Loader, Some(UpgradeKind::Downgrade)));
+        assert_eq!(manifest.delta(&update)[1], (Component::Ec, Some(UpgradeKind::MinorUpgrade)));
+    }
+}
@@ -0,0 +1,110 @@
+//! `Option<SemVer>` with the crate's settled meaning for a missing version.
+use core::cmp::Ordering;
+use core::fmt;
+use crate::{Error, SemVer};
+
+/// A version that may be unknown: displayed as `unknown`, sorted below every known
+/// version, and serialized as the zero record. Erased flash (all 0xFF) also reads back
+/// as unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MaybeVersion(pub Option<SemVer>);
+
+impl MaybeVersion {
+    pub const UNKNOWN: MaybeVersion = MaybeVersion(None);
+
+    pub fn is_known(&self) -> bool {
+        self.0.is_some()
+    }
+    /// Writes the 16-byte serialization to the start of `buf`, all zeros if unknown,
+    /// returning the number of bytes written. A known `v0.0.0-0` with no commit would
+    /// read back as unknown, so it is rejected with `Error::Malformed`.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        if self.0 == Some(SemVer::ZERO) {
+            return Err(Error::Malformed("v0.0.0-0 is the unknown sentinel"));
+        }
+        self.0.unwrap_or(SemVer::ZERO).write_to(buf)
+    }
+    /// Reads a 16-byte serialization from the start of `buf`. All zeros or all 0xFF
+    /// read as unknown; anything else must be a valid record.
+    pub fn read_from(buf: &[u8]) -> Result<MaybeVersion, Error> {
+        let bytes: &[u8; 16] = buf.get(..SemVer::SERIALIZED_LEN)
+            .and_then(|s| s.try_into().ok())
+            .ok_or(Error::Truncated)?;
+        if SemVer::is_unprogrammed(bytes) {
+            Ok(MaybeVersion(None))
+        } else {
+            SemVer::from_bytes_strict(bytes).map(|ver| MaybeVersion(Some(ver)))
+        }
+    }
+}
+
+impl From<Option<SemVer>> for MaybeVersion {
+    fn from(ver: Option<SemVer>) -> Self {
+        MaybeVersion(ver)
+    }
+}
+impl From<SemVer> for MaybeVersion {
+    fn from(ver: SemVer) -> Self {
+        MaybeVersion(Some(ver))
+    }
+}
+impl From<MaybeVersion> for Option<SemVer> {
+    fn from(ver: MaybeVersion) -> Self {
+        ver.0
+    }
+}
+
+impl PartialOrd for MaybeVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for MaybeVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+impl fmt::Display for MaybeVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some(ver) => fmt::Display::fmt(ver, f),
+            None => f.write_str("unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_maybe_version() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let known = MaybeVersion::from(ver);
+        assert_eq!(known.to_string(), "v0.9.8-760-gabcd1234");
+        assert_eq!(format!("{:#}", MaybeVersion::from(SemVer::new(1, 0, 0))), "v1.0.0");
+        assert_eq!(MaybeVersion::UNKNOWN.to_string(), "unknown");
+        assert!(MaybeVersion::UNKNOWN < MaybeVersion::from(SemVer::ZERO));
+        assert!(MaybeVersion::UNKNOWN < known);
+        assert_eq!([known, MaybeVersion::UNKNOWN].iter().max(), Some(&known));
+
+        let mut buf = [0xaau8; 16];
+        assert_eq!(MaybeVersion::UNKNOWN.write_to(&mut buf), Ok(16));
+        assert_eq!(buf, [0; 16]);
+        assert_eq!(MaybeVersion::read_from(&buf), Ok(MaybeVersion::UNKNOWN));
+        assert_eq!(MaybeVersion::read_from(&[0xff; 16]), Ok(MaybeVersion::UNKNOWN));
+        known.write_to(&mut buf).unwrap();
+        assert_eq!(MaybeVersion::read_from(&buf), Ok(known));
+        assert_eq!(Option::<SemVer>::from(MaybeVersion::read_from(&buf).unwrap()), Some(ver));
+        assert_eq!(MaybeVersion::read_from(&buf[..15]), Err(Error::Truncated));
+        assert_eq!(
+            MaybeVersion::from(SemVer::ZERO).write_to(&mut buf),
+            Err(Error::Malformed("v0.0.0-0 is the unknown sentinel"))
+        );
+        let zero_commit = MaybeVersion::from(SemVer { commit: Some(0), ..SemVer::ZERO });
+        zero_commit.write_to(&mut buf).unwrap();
+        assert_eq!(MaybeVersion::read_from(&buf), Ok(zero_commit));
+        buf[12] = 2;
+        assert_eq!(MaybeVersion::read_from(&buf), Err(Error::Malformed("invalid commit flag")));
+    }
+}
@@ -0,0 +1,154 @@
+//! MessagePack support via `rmp`, using the same layout as the CBOR encoding: an array
+//! `[maj, min, rev, extra]` with the commit appended as a fifth element when present.
+//! On the Python side this is just `msgpack.packb([0, 9, 8, 760, 0xabcd1234])`.
+//! As with CBOR, decoders skip any elements past the fifth.
+use rmp::decode::{self, RmpRead, ValueReadError};
+use rmp::encode::{self, RmpWrite};
+use rmp::Marker;
+use crate::{Error, SemVer};
+
+// reads a big-endian length of `width` bytes
+fn read_len<R: RmpRead>(rd: &mut R, width: usize) -> Result<u64, ValueReadError<R::Error>> {
+    let mut len = 0;
+    for _ in 0..width {
+        len = len << 8 | u64::from(rd.read_u8().map_err(ValueReadError::InvalidDataRead)?);
+    }
+    Ok(len)
+}
+
+// skips one value of any type, nested arrays and maps included
+fn skip_value<R: RmpRead>(rd: &mut R) -> Result<(), ValueReadError<R::Error>> {
+    // values still to skip, so that nesting doesn't recurse
+    let mut pending: u64 = 1;
+    while pending > 0 {
+        pending -= 1;
+        let (data, items) = match decode::read_marker(rd)? {
+            Marker::FixPos(_) | Marker::FixNeg(_) | Marker::Null | Marker::True | Marker::False => (0, 0),
+            Marker::U8 | Marker::I8 => (1, 0),
+            Marker::U16 | Marker::I16 => (2, 0),
+            Marker::U32 | Marker::I32 | Marker::F32 => (4, 0),
+            Marker::U64 | Marker::I64 | Marker::F64 => (8, 0),
+            Marker::FixStr(len) => (len.into(), 0),
+            Marker::Str8 | Marker::Bin8 => (read_len(rd, 1)?, 0),
+            Marker::Str16 | Marker::Bin16 => (read_len(rd, 2)?, 0),
+            Marker::Str32 | Marker::Bin32 => (read_len(rd, 4)?, 0),
+            // the type byte, then the data
+            Marker::FixExt1 => (2, 0),
+            Marker::FixExt2 => (3, 0),
+            Marker::FixExt4 => (5, 0),
+            Marker::FixExt8 => (9, 0),
+            Marker::FixExt16 => (17, 0),
+            Marker::Ext8 => (read_len(rd, 1)? + 1, 0),
+            Marker::Ext16 => (read_len(rd, 2)? + 1, 0),
+            Marker::Ext32 => (read_len(rd, 4)? + 1, 0),
+            Marker::FixArray(len) => (0, len.into()),
+            Marker::Array16 => (0, read_len(rd, 2)?),
+            Marker::Array32 => (0, read_len(rd, 4)?),
+            Marker::FixMap(len) => (0, 2 * u64::from(len)),
+            Marker::Map16 => (0, 2 * read_len(rd, 2)?),
+            Marker::Map32 => (0, 2 * read_len(rd, 4)?),
+            Marker::Reserved => return Err(ValueReadError::TypeMismatch(Marker::Reserved)),
+        };
+        for _ in 0..data {
+            rd.read_u8().map_err(ValueReadError::InvalidDataRead)?;
+        }
+        pending += items;
+    }
+    Ok(())
+}
+
+impl SemVer {
+    pub fn encode_msgpack<W: RmpWrite>(&self, wr: &mut W) -> Result<(), encode::ValueWriteError<W::Error>> {
+        encode::write_array_len(wr, if self.commit.is_some() { 5 } else { 4 })?;
+        for field in [self.maj, self.min, self.rev, self.extra] {
+            encode::write_uint(wr, field as u64)?;
+        }
+        if let Some(commit) = self.commit {
+            encode::write_uint(wr, commit as u64)?;
+        }
+        Ok(())
+    }
+    pub fn decode_msgpack<R: RmpRead>(rd: &mut R) -> Result<SemVer, decode::NumValueReadError<R::Error>> {
+        let len = decode::read_array_len(rd)?;
+        if len < 4 {
+            return Err(decode::NumValueReadError::OutOfRange);
+        }
+        let ver = SemVer {
+            maj: decode::read_int(rd)?,
+            min: decode::read_int(rd)?,
+            rev: decode::read_int(rd)?,
+            extra: decode::read_int(rd)?,
+            commit: if len >= 5 { Some(decode::read_int(rd)?) } else { None },
+        };
+        for _ in 5..len {
+            skip_value(rd)?;
+        }
+        Ok(ver)
+    }
+    /// Encodes into `buf`, returning the number of bytes written.
+    pub fn to_msgpack(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let total = buf.len();
+        let mut wr = &mut buf[..];
+        self.encode_msgpack(&mut wr).map_err(|_| Error::BufferTooSmall { needed: self.msgpack_len() })?;
+        Ok(total - wr.len())
+    }
+    pub fn from_msgpack(mut buf: &[u8]) -> Result<SemVer, Error> {
+        SemVer::decode_msgpack(&mut buf).map_err(|e| match e {
+            decode::NumValueReadError::InvalidMarkerRead(_) | decode::NumValueReadError::InvalidDataRead(_) =>
+                Error::Truncated,
+            _ => Error::Malformed("invalid msgpack version"),
+        })
+    }
+    fn msgpack_len(&self) -> usize {
+        fn uint_len(v: u64) -> usize {
+            match v {
+                0..=0x7f => 1,
+                0x80..=0xff => 2,
+                0x100..=0xffff => 3,
+                _ => 5,
+            }
+        }
+        1 + [self.maj, self.min, self.rev, self.extra].iter().map(|&f| uint_len(f as u64)).sum::<usize>()
+            + self.commit.map_or(0, |c| uint_len(c as u64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_msgpack() {
+        let mut buf = [0u8; 32];
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.to_msgpack(&mut buf), Ok(12));
+        // msgpack.packb([0, 9, 8, 760, 0xabcd1234])
+        assert_eq!(buf[..12], [0x95, 0x00, 0x09, 0x08, 0xcd, 0x02, 0xf8, 0xce, 0xab, 0xcd, 0x12, 0x34]);
+        assert_eq!(SemVer::from_msgpack(&buf).unwrap(), ver);
+        assert_eq!(ver.to_msgpack(&mut buf[..11]), Err(Error::BufferTooSmall { needed: 12 }));
+
+        let ver = SemVer::from_str("v1.2.3").unwrap();
+        assert_eq!(ver.to_msgpack(&mut buf), Ok(5));
+        assert_eq!(buf[..5], [0x94, 0x01, 0x02, 0x03, 0x00]);
+        assert_eq!(SemVer::from_msgpack(&buf[..5]).unwrap(), ver);
+
+        assert_eq!(SemVer::from_msgpack(&[0x94, 0x01, 0x02]), Err(Error::Truncated));
+        assert_eq!(SemVer::from_msgpack(&[0x93, 0x01, 0x02, 0x03]), Err(Error::Malformed("invalid msgpack version")));
+        // later additions are skipped: ["x", true], {1: 2} and ext type 1 [0xff]
+        let longer = [
+            0x98, 0x00, 0x09, 0x08, 0xcd, 0x02, 0xf8, 0xce, 0xab, 0xcd, 0x12, 0x34,
+            0x92, 0xa1, b'x', 0xc3, 0x81, 0x01, 0x02, 0xd4, 0x01, 0xff,
+        ];
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(SemVer::from_msgpack(&longer), Ok(ver));
+        assert_eq!(SemVer::from_msgpack(&longer[..longer.len() - 1]), Err(Error::Truncated));
+        assert_eq!(SemVer::from_msgpack(&longer[..17]), Err(Error::Truncated));
+        let mut reserved = longer;
+        reserved[12] = 0xc1;
+        assert_eq!(SemVer::from_msgpack(&reserved), Err(Error::Malformed("invalid msgpack version")));
+        // components that don't fit in a u16
+        assert_eq!(
+            SemVer::from_msgpack(&[0x94, 0xce, 0x00, 0x01, 0x00, 0x00, 0x02, 0x03, 0x00]),
+            Err(Error::Malformed("invalid msgpack version"))
+        );
+    }
+}
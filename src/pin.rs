@@ -0,0 +1,163 @@
+//! Pin files: a text snapshot of the exact component versions and manifest hashes a
+//! device was running, so that the firmware can be rebuilt for investigating field
+//! issues.
+//!
+//! ```text
+//! # comments and blank lines are ignored
+//! version soc v0.9.8-760-gabcd1234
+//! version ec v0.9.8-12-g0123abcd
+//! hash manifest 3f1c...e0
+//! ```
+use crate::{ParseError, SemVer};
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct PinFile {
+    pub versions: Vec<(String, SemVer)>,
+    /// Lowercase hex digests, keyed by the name of the manifest they cover.
+    pub hashes: Vec<(String, String)>,
+}
+
+/// One difference between a pin file and the environment it was checked against.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PinMismatch {
+    Version { name: String, pinned: SemVer, found: Option<SemVer> },
+    Hash { name: String, pinned: String, found: Option<String> },
+}
+
+/// Why a pin file failed to parse. `line` is 1-based.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PinError {
+    Syntax { line: usize, reason: &'static str },
+    Version { line: usize, reason: ParseError },
+}
+impl fmt::Display for PinError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PinError::Syntax { line, reason } => write!(f, "pin line {}: {}", line, reason),
+            PinError::Version { line, reason } => write!(f, "pin line {}: invalid version: {}", line, reason),
+        }
+    }
+}
+impl std::error::Error for PinError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PinError::Version { reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+impl PinFile {
+    pub fn parse(text: &str) -> Result<PinFile, PinError> {
+        let mut pin = PinFile::default();
+        let lines = text.lines().enumerate().map(|(i, l)| (i + 1, l.trim()));
+        for (line, text) in lines.filter(|(_, l)| !l.is_empty() && !l.starts_with('#')) {
+            let syntax = |reason| PinError::Syntax { line, reason };
+            let mut words = text.split_whitespace();
+            let kind = words.next().ok_or(syntax("empty line"))?;
+            let name = words.next().ok_or(syntax("missing a name"))?.to_string();
+            let value = words.next().ok_or(syntax("missing a value"))?;
+            if words.next().is_some() {
+                return Err(syntax("trailing fields"));
+            }
+            match kind {
+                "version" => pin.versions.push((name, SemVer::from_str(value)
+                    .map_err(|reason| PinError::Version { line, reason })?)),
+                "hash" => {
+                    if value.is_empty() || !value.chars().all(|c| c.is_ascii_hexdigit()) {
+                        return Err(syntax("hash is not hex"));
+                    }
+                    pin.hashes.push((name, value.to_ascii_lowercase()))
+                }
+                _ => return Err(syntax("unknown line type")),
+            }
+        }
+        Ok(pin)
+    }
+    /// Compares a build environment against this pin, returning every pinned entry that
+    /// is missing from or different in `current`. Extra entries in `current` are ignored.
+    pub fn reproduce_check(&self, current: &PinFile) -> Result<(), Vec<PinMismatch>> {
+        let mut mismatches = Vec::new();
+        for (name, pinned) in &self.versions {
+            let found = current.versions.iter().find(|(n, _)| n == name).map(|(_, v)| v);
+            if found != Some(pinned) {
+                mismatches.push(PinMismatch::Version {
+                    name: name.clone(),
+                    pinned: *pinned,
+                    found: found.copied(),
+                });
+            }
+        }
+        for (name, pinned) in &self.hashes {
+            let found = current.hashes.iter().find(|(n, _)| n == name).map(|(_, h)| h);
+            if found != Some(pinned) {
+                mismatches.push(PinMismatch::Hash {
+                    name: name.clone(),
+                    pinned: pinned.clone(),
+                    found: found.cloned(),
+                });
+            }
+        }
+        if mismatches.is_empty() { Ok(()) } else { Err(mismatches) }
+    }
+}
+impl fmt::Display for PinFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, ver) in &self.versions {
+            writeln!(f, "version {} {}", name, ver)?;
+        }
+        for (name, hash) in &self.hashes {
+            writeln!(f, "hash {} {}", name, hash)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_pin() {
+        let text = "# captured from a customer device\n\
+            version soc v0.9.8-760-gabcd1234\n\
+            \n\
+            version ec v0.9.8-12\n\
+            hash manifest 3F1C00E0\n";
+        let pin = PinFile::parse(text).unwrap();
+        assert_eq!(pin.versions.len(), 2);
+        assert_eq!(pin.hashes, vec![("manifest".to_string(), "3f1c00e0".to_string())]);
+        assert_eq!(
+            pin.to_string(),
+            "version soc v0.9.8-760-gabcd1234\nversion ec v0.9.8-12\nhash manifest 3f1c00e0\n"
+        );
+        assert_eq!(PinFile::parse(&pin.to_string()).unwrap(), pin);
+        assert_eq!(pin.reproduce_check(&pin), Ok(()));
+
+        let mut env = PinFile::parse("version soc v0.9.8-760-g1234\nhash manifest 3f1c00e0\nhash extra 00\n").unwrap();
+        assert_eq!(
+            pin.reproduce_check(&env),
+            Err(vec![
+                PinMismatch::Version {
+                    name: "soc".to_string(),
+                    pinned: SemVer::from_str("v0.9.8-760-gabcd1234").unwrap(),
+                    found: Some(SemVer::from_str("v0.9.8-760-g1234").unwrap()),
+                },
+                PinMismatch::Version {
+                    name: "ec".to_string(),
+                    pinned: SemVer::from_str("v0.9.8-12").unwrap(),
+                    found: None,
+                },
+            ])
+        );
+        env.hashes.clear();
+        assert_eq!(pin.reproduce_check(&env).unwrap_err().len(), 3);
+
+        assert_eq!(PinFile::parse("version soc"), Err(PinError::Syntax { line: 1, reason: "missing a value" }));
+        let err = PinFile::parse("# header\n\nversion soc 0.9.8").unwrap_err();
+        assert_eq!(err, PinError::Version { line: 3, reason: ParseError::MissingPrefix });
+        assert_eq!(err.to_string(), format!("pin line 3: invalid version: {}", ParseError::MissingPrefix));
+        assert_eq!(PinFile::parse("hash manifest xyz"), Err(PinError::Syntax { line: 1, reason: "hash is not hex" }));
+        assert_eq!(PinFile::parse("channel soc beta"), Err(PinError::Syntax { line: 1, reason: "unknown line type" }));
+    }
+}
@@ -0,0 +1,74 @@
+//! Planning multi-step updates through required stepping-stone versions.
+use core::fmt;
+use crate::SemVer;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanError {
+    /// The target is older than the running version.
+    Downgrade { current: SemVer, target: SemVer },
+}
+impl fmt::Display for PlanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanError::Downgrade { current, target } => write!(f, "cannot plan a downgrade from {} to {}", current, target),
+        }
+    }
+}
+impl core::error::Error for PlanError {}
+
+/// Plans the installs that take a device from `current` to `target`: every waypoint
+/// strictly between the two, oldest first, then `target` itself. Waypoints outside that
+/// span are skipped, so the whole list of required stepping stones can be passed in any
+/// order. Updating to the running version is an empty plan.
+pub fn plan_updates<'a>(current: &SemVer, target: &SemVer, waypoints: &'a [SemVer]) -> Result<UpdatePlan<'a>, PlanError> {
+    if target < current {
+        return Err(PlanError::Downgrade { current: *current, target: *target });
+    }
+    Ok(UpdatePlan { last: *current, target: *target, waypoints, done: target == current })
+}
+
+/// The installs from `plan_updates`, in order.
+#[derive(Debug, Clone)]
+pub struct UpdatePlan<'a> {
+    last: SemVer,
+    target: SemVer,
+    waypoints: &'a [SemVer],
+    done: bool,
+}
+
+impl Iterator for UpdatePlan<'_> {
+    type Item = SemVer;
+    fn next(&mut self) -> Option<SemVer> {
+        if self.done {
+            return None;
+        }
+        // the oldest waypoint not yet passed; tables are short, so a scan beats sorting
+        let next = self.waypoints.iter()
+            .filter(|w| **w > self.last && **w < self.target)
+            .min()
+            .copied()
+            .unwrap_or_else(|| {
+                self.done = true;
+                self.target
+            });
+        self.last = next;
+        Some(next)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_plan_updates() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let waypoints = [v("v1.0.0"), v("v0.9.6"), v("v0.9.2"), v("v0.9.6")];
+        let plan = |from, to| plan_updates(&v(from), &v(to), &waypoints).map(|p| p.collect::<Vec<_>>());
+        assert_eq!(plan("v0.9.0", "v1.2.0"), Ok(vec![v("v0.9.2"), v("v0.9.6"), v("v1.0.0"), v("v1.2.0")]));
+        assert_eq!(plan("v0.9.4-40-gabcd1234", "v1.0.0"), Ok(vec![v("v0.9.6"), v("v1.0.0")]));
+        assert_eq!(plan("v0.9.6", "v0.9.8"), Ok(vec![v("v0.9.8")]));
+        assert_eq!(plan("v0.9.8", "v0.9.8"), Ok(vec![]));
+        assert_eq!(plan("v1.0.0", "v0.9.8"), Err(PlanError::Downgrade { current: v("v1.0.0"), target: v("v0.9.8") }));
+        assert_eq!(plan_updates(&v("v0.1.0"), &v("v0.2.0"), &[]).unwrap().collect::<Vec<_>>(), [v("v0.2.0")]);
+    }
+}
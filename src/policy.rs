@@ -0,0 +1,53 @@
+//! Pluggable orderings for `SemVer::cmp_with`.
+use core::cmp::Ordering;
+use crate::SemVer;
+
+/// A way of ordering versions, for subsystems whose tie-breaking differs from `Ord`.
+/// Closures `Fn(&SemVer, &SemVer) -> Ordering` are policies too.
+pub trait OrderingPolicy {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering;
+}
+impl<F: Fn(&SemVer, &SemVer) -> Ordering> OrderingPolicy for F {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering {
+        self(a, b)
+    }
+}
+
+/// `SemVer`'s own `Ord`: `maj.min.rev-extra`, ignoring the commit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+impl OrderingPolicy for Standard {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering {
+        a.cmp(b)
+    }
+}
+
+/// Compares `maj.min.rev` only, so every build from the same tag is equal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IgnoreExtra;
+impl OrderingPolicy for IgnoreExtra {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering {
+        (a.maj, a.min, a.rev).cmp(&(b.maj, b.min, b.rev))
+    }
+}
+
+/// `SemVer::cmp_with_commit`: a total order that breaks ties on the commit.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WithCommit;
+impl OrderingPolicy for WithCommit {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering {
+        a.cmp_with_commit(b)
+    }
+}
+
+/// Like `Standard`, except that within the same `maj.min.rev` a tagged release ranks
+/// above every dev build, for policies that prefer known releases over newer dev builds.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReleaseFirst;
+impl OrderingPolicy for ReleaseFirst {
+    fn cmp(&self, a: &SemVer, b: &SemVer) -> Ordering {
+        IgnoreExtra.cmp(a, b)
+            .then(a.is_release().cmp(&b.is_release()))
+            .then(a.cmp(b))
+    }
+}
@@ -0,0 +1,145 @@
+//! Version ranges, such as the compatibility window stored in a flash manifest.
+//!
+//! The binary form is 36 bytes: the 16-byte serializations of `min` and `max`, then a
+//! little-endian flags word with bit 0 set if `min` is inclusive and bit 1 set if `max`
+//! is inclusive.
+use core::cmp::Ordering;
+use core::fmt;
+use crate::{Error, SemVer};
+
+const MIN_INCLUSIVE: u32 = 1;
+const MAX_INCLUSIVE: u32 = 2;
+
+/// A range of versions between `min` and `max`, each end inclusive or exclusive.
+/// Containment uses `SemVer`'s ordering, so commits are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub struct VersionRange {
+    pub min: SemVer,
+    pub max: SemVer,
+    pub min_inclusive: bool,
+    pub max_inclusive: bool,
+}
+
+impl VersionRange {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 36;
+
+    /// `min..=max`
+    pub const fn inclusive(min: SemVer, max: SemVer) -> Self {
+        VersionRange { min, max, min_inclusive: true, max_inclusive: true }
+    }
+    /// `min..max`
+    pub const fn half_open(min: SemVer, max: SemVer) -> Self {
+        VersionRange { min, max, min_inclusive: true, max_inclusive: false }
+    }
+    pub fn contains(&self, ver: &SemVer) -> bool {
+        let above = if self.min_inclusive { *ver >= self.min } else { *ver > self.min };
+        let below = if self.max_inclusive { *ver <= self.max } else { *ver < self.max };
+        above && below
+    }
+    /// True if no version can be in the range.
+    pub fn is_empty(&self) -> bool {
+        match self.min.cmp(&self.max) {
+            Ordering::Greater => true,
+            Ordering::Equal => !(self.min_inclusive && self.max_inclusive),
+            Ordering::Less => {
+                // with both ends exclusive, adjacent versions leave nothing in between
+                !self.min_inclusive && !self.max_inclusive && u64::from(self.min) + 1 == u64::from(self.max)
+            }
+        }
+    }
+    /// The versions in both ranges. The result may be empty; check `is_empty`.
+    pub fn intersect(&self, other: &VersionRange) -> VersionRange {
+        let (min, min_inclusive) = match self.min.cmp(&other.min) {
+            Ordering::Greater => (self.min, self.min_inclusive),
+            Ordering::Less => (other.min, other.min_inclusive),
+            Ordering::Equal => (self.min, self.min_inclusive && other.min_inclusive),
+        };
+        let (max, max_inclusive) = match self.max.cmp(&other.max) {
+            Ordering::Less => (self.max, self.max_inclusive),
+            Ordering::Greater => (other.max, other.max_inclusive),
+            Ordering::Equal => (self.max, self.max_inclusive && other.max_inclusive),
+        };
+        VersionRange { min, max, min_inclusive, max_inclusive }
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..VersionRange::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: VersionRange::SERIALIZED_LEN })?;
+        let flags = if self.min_inclusive { MIN_INCLUSIVE } else { 0 }
+            | if self.max_inclusive { MAX_INCLUSIVE } else { 0 };
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.min));
+        dest[16..32].copy_from_slice(&<[u8; 16]>::from(&self.max));
+        dest[32..36].copy_from_slice(&flags.to_le_bytes());
+        Ok(VersionRange::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<VersionRange, Error> {
+        let (min, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (max, rest) = rest.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (flags, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let flags = u32::from_le_bytes(*flags);
+        if flags & !(MIN_INCLUSIVE | MAX_INCLUSIVE) != 0 {
+            return Err(Error::Malformed("unknown range flags"));
+        }
+        Ok(VersionRange {
+            min: SemVer::from_bytes_strict(min)?,
+            max: SemVer::from_bytes_strict(max)?,
+            min_inclusive: flags & MIN_INCLUSIVE != 0,
+            max_inclusive: flags & MAX_INCLUSIVE != 0,
+        })
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f, "{}{}, {}{}",
+            if self.min_inclusive { '[' } else { '(' }, self.min,
+            self.max, if self.max_inclusive { ']' } else { ')' }
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_range() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let window = VersionRange::half_open(v("v0.9.8"), v("v0.10.0"));
+        assert!(window.contains(&v("v0.9.8")));
+        assert!(window.contains(&v("v0.9.12-40-gabcd1234")));
+        assert!(!window.contains(&v("v0.10.0")));
+        assert!(!window.contains(&v("v0.9.7-999")));
+        assert!(!window.is_empty());
+        assert_eq!(window.to_string(), "[v0.9.8-0, v0.10.0-0)");
+
+        let other = VersionRange { min_inclusive: false, ..VersionRange::inclusive(v("v0.9.8"), v("v0.9.9")) };
+        let both = window.intersect(&other);
+        assert_eq!(both, VersionRange { min: v("v0.9.8"), max: v("v0.9.9"), min_inclusive: false, max_inclusive: true });
+        assert!(!both.contains(&v("v0.9.8")));
+        assert!(both.contains(&v("v0.9.8-1")));
+
+        let later = VersionRange::inclusive(v("v0.10.0"), v("v0.11.0"));
+        assert!(window.intersect(&later).is_empty());
+        assert!(!VersionRange::inclusive(v("v0.10.0"), v("v0.10.0")).is_empty());
+        assert!(VersionRange::half_open(v("v0.10.0"), v("v0.10.0")).is_empty());
+        assert!(VersionRange { min_inclusive: false, ..VersionRange::half_open(v("v0.10.0"), v("v0.10.0-1")) }.is_empty());
+        assert!(VersionRange::inclusive(v("v1.0.0"), v("v0.1.0")).is_empty());
+    }
+    #[test]
+    fn test_range_binary() {
+        let range = VersionRange::half_open(SemVer::new(0, 9, 8), SemVer::from_str("v0.10.0-0-gabcd1234").unwrap());
+        let mut buf = [0u8; 40];
+        assert_eq!(range.write_to(&mut buf), Ok(36));
+        assert_eq!(buf[32..36], [1, 0, 0, 0]);
+        assert_eq!(VersionRange::read_from(&buf), Ok(range));
+        assert_eq!(VersionRange::read_from(&buf[..35]), Err(Error::Truncated));
+        assert_eq!(range.write_to(&mut buf[..35]), Err(Error::BufferTooSmall { needed: 36 }));
+        buf[32] = 4;
+        assert_eq!(VersionRange::read_from(&buf), Err(Error::Malformed("unknown range flags")));
+    }
+}
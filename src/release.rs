@@ -0,0 +1,172 @@
+//! Newtypes that pin down which kind of version an API accepts.
+use core::cmp::Ordering;
+use core::fmt;
+use core::ops::Deref;
+use crate::SemVer;
+
+/// A tagged release: `extra` is 0 and there is no commit. APIs that must only see
+/// releases, such as signing and publishing, take this instead of `SemVer`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseVersion(SemVer);
+
+/// The error from converting a version that isn't a tagged release into a
+/// `ReleaseVersion`. Carries the rejected version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotARelease(pub SemVer);
+impl fmt::Display for NotARelease {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not a tagged release", self.0)
+    }
+}
+impl core::error::Error for NotARelease {}
+
+impl ReleaseVersion {
+    pub const fn new(maj: u16, min: u16, rev: u16) -> Self {
+        ReleaseVersion(SemVer::new(maj, min, rev))
+    }
+    pub const fn get(&self) -> SemVer {
+        self.0
+    }
+}
+
+impl TryFrom<SemVer> for ReleaseVersion {
+    type Error = NotARelease;
+    fn try_from(ver: SemVer) -> Result<Self, Self::Error> {
+        if ver.is_release() { Ok(ReleaseVersion(ver)) } else { Err(NotARelease(ver)) }
+    }
+}
+impl From<ReleaseVersion> for SemVer {
+    fn from(ver: ReleaseVersion) -> SemVer {
+        ver.0
+    }
+}
+impl Deref for ReleaseVersion {
+    type Target = SemVer;
+    fn deref(&self) -> &SemVer {
+        &self.0
+    }
+}
+impl fmt::Display for ReleaseVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// A build that identifies its commit, as the crash reporter requires so dev telemetry
+/// can always be traced back to source. Releases built from a tag have a commit too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DevBuild(SemVer);
+
+/// The error from converting a version without a commit into a `DevBuild`. Carries the
+/// rejected version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoCommit(pub SemVer);
+impl fmt::Display for NoCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} does not identify a commit", self.0)
+    }
+}
+impl core::error::Error for NoCommit {}
+
+impl DevBuild {
+    pub const fn get(&self) -> SemVer {
+        self.0
+    }
+    /// The commit, which is always present.
+    pub fn commit(&self) -> u32 {
+        self.0.commit.unwrap_or(0)
+    }
+}
+
+impl TryFrom<SemVer> for DevBuild {
+    type Error = NoCommit;
+    fn try_from(ver: SemVer) -> Result<Self, Self::Error> {
+        if ver.has_commit() { Ok(DevBuild(ver)) } else { Err(NoCommit(ver)) }
+    }
+}
+impl From<DevBuild> for SemVer {
+    fn from(ver: DevBuild) -> SemVer {
+        ver.0
+    }
+}
+impl Deref for DevBuild {
+    type Target = SemVer;
+    fn deref(&self) -> &SemVer {
+        &self.0
+    }
+}
+impl fmt::Display for DevBuild {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+// the newtypes compare with plain versions exactly as the versions they wrap do, so
+// policy code can check `candidate >= min_release` without unwrapping either side
+macro_rules! compare_with_semver {
+    ($($t:ty),*) => {$(
+        impl PartialEq<SemVer> for $t {
+            fn eq(&self, other: &SemVer) -> bool {
+                self.0 == *other
+            }
+        }
+        impl PartialEq<$t> for SemVer {
+            fn eq(&self, other: &$t) -> bool {
+                *self == other.0
+            }
+        }
+        impl PartialOrd<SemVer> for $t {
+            fn partial_cmp(&self, other: &SemVer) -> Option<Ordering> {
+                Some(self.0.cmp(other))
+            }
+        }
+        impl PartialOrd<$t> for SemVer {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(self.cmp(&other.0))
+            }
+        }
+    )*};
+}
+compare_with_semver!(ReleaseVersion, DevBuild);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_release_version() {
+        let release = ReleaseVersion::try_from(SemVer::from_str("v0.9.8").unwrap()).unwrap();
+        assert_eq!(release, ReleaseVersion::new(0, 9, 8));
+        assert_eq!(SemVer::from(release), SemVer::new(0, 9, 8));
+        assert_eq!(release.maj, 0);
+        assert_eq!(format!("{:#}", release), "v0.9.8");
+        let dev = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ReleaseVersion::try_from(dev), Err(NotARelease(dev)));
+        assert_eq!(NotARelease(dev).to_string(), "v0.9.8-760-gabcd1234 is not a tagged release");
+        let tagged_build = SemVer::from_str("v0.9.8-0-gabcd1234").unwrap();
+        assert!(ReleaseVersion::try_from(tagged_build).is_err());
+    }
+    #[test]
+    fn test_dev_build() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let dev = DevBuild::try_from(ver).unwrap();
+        assert_eq!(dev.commit(), 0xabcd1234);
+        assert_eq!(dev.extra, 760);
+        assert_eq!(SemVer::from(dev), ver);
+        assert_eq!(dev.to_string(), "v0.9.8-760-gabcd1234");
+        assert!(DevBuild::try_from(SemVer::from_str("v0.9.8-0-gabcd1234").unwrap()).is_ok());
+        let uncommitted = SemVer::from_str("v0.9.8-760").unwrap();
+        assert_eq!(DevBuild::try_from(uncommitted), Err(NoCommit(uncommitted)));
+        assert_eq!(NoCommit(uncommitted).to_string(), "v0.9.8-760 does not identify a commit");
+    }
+    #[test]
+    fn test_compare_with_semver() {
+        let release = ReleaseVersion::new(0, 9, 8);
+        let dev = DevBuild::try_from(SemVer::from_str("v0.9.8-760-gabcd1234").unwrap()).unwrap();
+        assert!(release == SemVer::new(0, 9, 8));
+        assert!(SemVer::new(0, 9, 8) == release);
+        assert!(SemVer::new(0, 9, 9) > release);
+        assert!(dev > SemVer::new(0, 9, 8));
+        assert!(dev != SemVer::from_str("v0.9.8-760").unwrap());
+        assert!(SemVer::from_str("v0.9.8-760").unwrap() <= dev);
+    }
+}
@@ -0,0 +1,106 @@
+//! Layered version resolution for builds that may not have a git checkout, such as CI
+//! builds from source tarballs.
+use std::fmt;
+use crate::{GitError, ParseError, SemVer};
+
+/// Where `SemVer::resolve` found the version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionSource {
+    /// The `XOUS_SEMVER_OVERRIDE` environment variable.
+    Env,
+    /// `git describe` on the current directory.
+    Git,
+    /// The `CARGO_PKG_VERSION` of the crate being built.
+    CargoPkg,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResolveError {
+    /// The override variable is set but does not parse. This is not silently skipped,
+    /// since it almost always means a typo in CI configuration.
+    BadOverride(ParseError),
+    /// Git failed, and `CARGO_PKG_VERSION` was unset or unusable.
+    Unresolved { git: GitError },
+}
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::BadOverride(e) => write!(f, "{} is not a valid version: {}", SemVer::OVERRIDE_ENV, e),
+            ResolveError::Unresolved { git } => write!(f, "no version source available; git: {}", git),
+        }
+    }
+}
+impl std::error::Error for ResolveError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ResolveError::BadOverride(e) => Some(e),
+            ResolveError::Unresolved { git } => Some(git),
+        }
+    }
+}
+
+impl SemVer {
+    /// Environment variable that, when set, overrides every other version source.
+    pub const OVERRIDE_ENV: &'static str = "XOUS_SEMVER_OVERRIDE";
+
+    /// Resolves the build version from, in order: the `XOUS_SEMVER_OVERRIDE` environment
+    /// variable, `git describe`, and `CARGO_PKG_VERSION` (as set by cargo for build
+    /// scripts). Returns the version along with the source that provided it.
+    pub fn resolve() -> Result<(SemVer, VersionSource), ResolveError> {
+        resolve_with(|name| std::env::var(name).ok(), SemVer::from_git)
+    }
+}
+
+fn resolve_with<E, G>(env: E, git: G) -> Result<(SemVer, VersionSource), ResolveError>
+where
+    E: Fn(&str) -> Option<String>,
+    G: FnOnce() -> Result<SemVer, GitError>,
+{
+    if let Some(ver) = env(SemVer::OVERRIDE_ENV) {
+        return SemVer::from_str(&ver)
+            .map(|v| (v, VersionSource::Env))
+            .map_err(ResolveError::BadOverride);
+    }
+    let git_err = match git() {
+        Ok(ver) => return Ok((ver, VersionSource::Git)),
+        Err(e) => e,
+    };
+    env("CARGO_PKG_VERSION")
+        .and_then(|v| SemVer::from_cargo_version(&v).ok())
+        .map(|v| (v, VersionSource::CargoPkg))
+        .ok_or(ResolveError::Unresolved { git: git_err })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |name| vars.iter().find(|(n, _)| *n == name).map(|(_, v)| v.to_string())
+    }
+    #[test]
+    fn test_resolve() {
+        let git_ok = || SemVer::from_str("v0.9.8-760-gabcd1234").map_err(|_| GitError::NoTags);
+        let git_fail = || Err(GitError::NoTags);
+        assert_eq!(
+            resolve_with(env(&[("XOUS_SEMVER_OVERRIDE", "v1.0.0"), ("CARGO_PKG_VERSION", "0.1.3")]), git_ok),
+            Ok((SemVer::new(1, 0, 0), VersionSource::Env))
+        );
+        assert_eq!(
+            resolve_with(env(&[("XOUS_SEMVER_OVERRIDE", "1.0.0")]), git_ok),
+            Err(ResolveError::BadOverride(ParseError::MissingPrefix))
+        );
+        assert_eq!(
+            resolve_with(env(&[("CARGO_PKG_VERSION", "0.1.3")]), git_ok),
+            Ok((SemVer::from_str("v0.9.8-760-gabcd1234").unwrap(), VersionSource::Git))
+        );
+        assert_eq!(
+            resolve_with(env(&[("CARGO_PKG_VERSION", "0.1.3")]), git_fail),
+            Ok((SemVer::new(0, 1, 3), VersionSource::CargoPkg))
+        );
+        assert_eq!(
+            resolve_with(env(&[("CARGO_PKG_VERSION", "0.1.3-rc.1")]), git_fail),
+            Err(ResolveError::Unresolved { git: GitError::NoTags })
+        );
+        assert_eq!(resolve_with(env(&[]), git_fail), Err(ResolveError::Unresolved { git: GitError::NoTags }));
+    }
+}
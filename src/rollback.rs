@@ -0,0 +1,138 @@
+//! Rollback protection: deciding whether a candidate image may replace the running one.
+use crate::{Error, SemVer};
+
+/// The outcome of `SemVer::is_allowed_upgrade`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpgradeCheck {
+    /// The candidate may be installed. Reinstalling the current version is allowed.
+    Allowed,
+    /// The candidate is older than the running version.
+    Downgrade,
+    /// The candidate is older than the security floor, regardless of what is running.
+    BelowFloor,
+    /// The device runs a tagged release and the candidate is a dev build.
+    DevOntoProd,
+    /// The candidate's anti-rollback counter is below the device's.
+    CounterRollback,
+}
+
+impl UpgradeCheck {
+    pub fn is_allowed(&self) -> bool {
+        *self == UpgradeCheck::Allowed
+    }
+}
+
+impl SemVer {
+    /// Checks whether `self`, a candidate image, may replace `current` on a device whose
+    /// security floor is `floor`. Checks run in order of severity: the floor, then
+    /// downgrades, then dev builds onto release devices. Commits are not compared.
+    pub fn is_allowed_upgrade(&self, current: &SemVer, floor: &SemVer) -> UpgradeCheck {
+        if self < floor {
+            UpgradeCheck::BelowFloor
+        } else if self < current {
+            UpgradeCheck::Downgrade
+        } else if current.is_release() && self.is_dev() {
+            UpgradeCheck::DevOntoProd
+        } else {
+            UpgradeCheck::Allowed
+        }
+    }
+}
+
+/// A version paired with a monotonic anti-rollback counter, as stored on the device
+/// and carried by each image. The counter lets a security fix forbid rollback to older
+/// images even where their versions would otherwise be acceptable.
+///
+/// The binary form is the 16-byte version serialization then the little-endian counter,
+/// 20 bytes in total.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature="arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RollbackGuard {
+    pub version: SemVer,
+    pub counter: u32,
+}
+
+impl RollbackGuard {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = SemVer::SERIALIZED_LEN + 4;
+
+    /// Checks an image carrying `candidate` against the device state in `self`. Both the
+    /// version and the counter must not go backwards; a counter rollback is reported
+    /// first, since it is the stronger statement.
+    pub fn permits(&self, candidate: &RollbackGuard) -> UpgradeCheck {
+        if candidate.counter < self.counter {
+            UpgradeCheck::CounterRollback
+        } else if candidate.version < self.version {
+            UpgradeCheck::Downgrade
+        } else {
+            UpgradeCheck::Allowed
+        }
+    }
+    /// Records that `installed` was installed: each field moves up to the installed
+    /// value but never down.
+    pub fn advance(&mut self, installed: &RollbackGuard) {
+        self.version = self.version.max(installed.version);
+        self.counter = self.counter.max(installed.counter);
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..RollbackGuard::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: RollbackGuard::SERIALIZED_LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.version));
+        dest[16..].copy_from_slice(&self.counter.to_le_bytes());
+        Ok(RollbackGuard::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<RollbackGuard, Error> {
+        let (version, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (counter, _) = rest.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        Ok(RollbackGuard {
+            version: SemVer::from_bytes_strict(version)?,
+            counter: u32::from_le_bytes(*counter),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_allowed_upgrade() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let floor = v("v0.9.5");
+        let prod = v("v0.9.8");
+        let dev = v("v0.9.8-760-gabcd1234");
+        assert_eq!(v("v0.9.9").is_allowed_upgrade(&prod, &floor), UpgradeCheck::Allowed);
+        assert!(prod.is_allowed_upgrade(&prod, &floor).is_allowed());
+        assert_eq!(v("v0.9.7").is_allowed_upgrade(&prod, &floor), UpgradeCheck::Downgrade);
+        assert_eq!(v("v0.9.4").is_allowed_upgrade(&prod, &floor), UpgradeCheck::BelowFloor);
+        assert_eq!(v("v0.9.4").is_allowed_upgrade(&v("v0.9.0"), &floor), UpgradeCheck::BelowFloor);
+        assert_eq!(dev.is_allowed_upgrade(&prod, &floor), UpgradeCheck::DevOntoProd);
+        assert_eq!(v("v0.9.8-761-g12345678").is_allowed_upgrade(&dev, &floor), UpgradeCheck::Allowed);
+        assert_eq!(v("v0.9.9").is_allowed_upgrade(&dev, &floor), UpgradeCheck::Allowed);
+    }
+    #[test]
+    fn test_rollback_guard() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let mut device = RollbackGuard { version: v("v0.9.8"), counter: 3 };
+        let newer = RollbackGuard { version: v("v0.9.9"), counter: 3 };
+        let old_counter = RollbackGuard { version: v("v0.10.0"), counter: 2 };
+        let older = RollbackGuard { version: v("v0.9.7"), counter: 4 };
+        assert_eq!(device.permits(&newer), UpgradeCheck::Allowed);
+        assert_eq!(device.permits(&device), UpgradeCheck::Allowed);
+        assert_eq!(device.permits(&old_counter), UpgradeCheck::CounterRollback);
+        assert_eq!(device.permits(&older), UpgradeCheck::Downgrade);
+
+        device.advance(&older);
+        assert_eq!(device, RollbackGuard { version: v("v0.9.8"), counter: 4 });
+        device.advance(&newer);
+        assert_eq!(device, RollbackGuard { version: v("v0.9.9"), counter: 4 });
+
+        let mut buf = [0u8; 20];
+        assert_eq!(device.write_to(&mut buf), Ok(20));
+        assert_eq!(buf[16..], [4, 0, 0, 0]);
+        assert_eq!(RollbackGuard::read_from(&buf), Ok(device));
+        assert_eq!(RollbackGuard::read_from(&buf[..19]), Err(Error::Truncated));
+        assert_eq!(device.write_to(&mut buf[..19]), Err(Error::BufferTooSmall { needed: 20 }));
+    }
+}
@@ -0,0 +1,62 @@
+// serde helpers shared by the derived impls.
+
+// The commit is written as an 8-digit lowercase hex string ("abcd1234"), matching what
+// `git describe` prints. Payloads from before that change stored it as a plain number,
+// so both forms are accepted on input.
+pub(crate) mod commit {
+    use core::fmt;
+    use serde::de::{self, Visitor};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(commit: &Option<u32>, s: S) -> Result<S::Ok, S::Error> {
+        match commit {
+            Some(c) => {
+                let mut hex = [0u8; 8];
+                crate::hex::encode(&c.to_be_bytes(), &mut hex);
+                s.serialize_some(core::str::from_utf8(&hex).unwrap())
+            }
+            None => s.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Option<u32>, D::Error> {
+        d.deserialize_option(OptionVisitor)
+    }
+
+    struct OptionVisitor;
+    impl<'de> Visitor<'de> for OptionVisitor {
+        type Value = Option<u32>;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a hex commit string, an integer, or null")
+        }
+        fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+        fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+        fn visit_some<D: Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+            d.deserialize_any(CommitVisitor).map(Some)
+        }
+    }
+
+    struct CommitVisitor;
+    impl Visitor<'_> for CommitVisitor {
+        type Value = u32;
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a hex commit string or an integer")
+        }
+        fn visit_u64<E: de::Error>(self, v: u64) -> Result<u32, E> {
+            u32::try_from(v).map_err(|_| E::custom("commit out of range"))
+        }
+        fn visit_i64<E: de::Error>(self, v: i64) -> Result<u32, E> {
+            u32::try_from(v).map_err(|_| E::custom("commit out of range"))
+        }
+        fn visit_str<E: de::Error>(self, v: &str) -> Result<u32, E> {
+            if v.is_empty() || v.len() > 8 {
+                return Err(E::custom("commit must be 1 to 8 hex digits"));
+            }
+            u32::from_str_radix(v, 16).map_err(|_| E::custom("invalid hex commit"))
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! A fixed-capacity set of versions, for tracking which versions a device has seen
+//! without an allocator.
+use core::cmp::Ordering;
+use crate::{Error, SemVer};
+
+/// Up to `N` distinct versions, kept sorted. Versions that differ only in their commit
+/// are distinct, and sort by commit after the usual ordering. Serializes as the sorted
+/// 16-byte records back to back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionSet<const N: usize> {
+    versions: [SemVer; N],
+    len: usize,
+}
+
+// the usual ordering, made total by breaking ties on the commit
+fn total_cmp(a: &SemVer, b: &SemVer) -> Ordering {
+    a.cmp(b).then(a.commit.cmp(&b.commit))
+}
+
+impl<const N: usize> VersionSet<N> {
+    pub const fn new() -> Self {
+        VersionSet { versions: [SemVer::ZERO; N], len: 0 }
+    }
+    /// Adds `ver`, returning `Ok(false)` if it was already present, or
+    /// `Error::Overflow` if the set is full.
+    pub fn insert(&mut self, ver: SemVer) -> Result<bool, Error> {
+        let at = match self.as_slice().binary_search_by(|v| total_cmp(v, &ver)) {
+            Ok(_) => return Ok(false),
+            Err(at) => at,
+        };
+        if self.len == N {
+            return Err(Error::Overflow("set capacity"));
+        }
+        self.versions[at..=self.len].rotate_right(1);
+        self.versions[at] = ver;
+        self.len += 1;
+        Ok(true)
+    }
+    pub fn contains(&self, ver: &SemVer) -> bool {
+        self.as_slice().binary_search_by(|v| total_cmp(v, ver)).is_ok()
+    }
+    pub fn max(&self) -> Option<SemVer> {
+        self.as_slice().last().copied()
+    }
+    pub fn min(&self) -> Option<SemVer> {
+        self.as_slice().first().copied()
+    }
+    /// The versions in ascending order.
+    pub fn as_slice(&self) -> &[SemVer] {
+        &self.versions[..self.len]
+    }
+    pub fn iter(&self) -> core::slice::Iter<'_, SemVer> {
+        self.as_slice().iter()
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    pub fn serialized_len(&self) -> usize {
+        self.len * SemVer::SERIALIZED_LEN
+    }
+    /// Writes the records to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_len();
+        let dest = buf.get_mut(..needed).ok_or(Error::BufferTooSmall { needed })?;
+        for (chunk, ver) in dest.chunks_exact_mut(SemVer::SERIALIZED_LEN).zip(self.iter()) {
+            chunk.copy_from_slice(&ver.to_le_bytes());
+        }
+        Ok(needed)
+    }
+    /// Reads back-to-back records from `buf`, stopping at the end or at the first erased
+    /// (all 0xFF) record, so a flash region that versions are appended to reads
+    /// directly. The records may be in any order and may repeat.
+    pub fn read_from(buf: &[u8]) -> Result<Self, Error> {
+        let records = buf.chunks_exact(SemVer::SERIALIZED_LEN);
+        if !records.remainder().is_empty() {
+            return Err(Error::Truncated);
+        }
+        let mut set = VersionSet::new();
+        for record in records {
+            let bytes: &[u8; 16] = record.try_into().map_err(|_| Error::Truncated)?;
+            if bytes.iter().all(|&b| b == 0xff) {
+                break;
+            }
+            set.insert(SemVer::from_bytes_strict(bytes)?)?;
+        }
+        Ok(set)
+    }
+}
+
+impl<const N: usize> Default for VersionSet<N> {
+    fn default() -> Self {
+        VersionSet::new()
+    }
+}
+impl<'a, const N: usize> IntoIterator for &'a VersionSet<N> {
+    type Item = &'a SemVer;
+    type IntoIter = core::slice::Iter<'a, SemVer>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_version_set() {
+        let mut set = VersionSet::<3>::new();
+        assert_eq!(set.max(), None);
+        assert_eq!(set.insert(SemVer::new(0, 9, 9)), Ok(true));
+        assert_eq!(set.insert(SemVer::new(0, 9, 8)), Ok(true));
+        assert_eq!(set.insert(SemVer::new(0, 9, 9)), Ok(false));
+        let dev = SemVer::from_str("v0.9.8-0-gabcd1234").unwrap();
+        assert_eq!(set.insert(dev), Ok(true));
+        assert_eq!(set.as_slice(), [SemVer::new(0, 9, 8), dev, SemVer::new(0, 9, 9)]);
+        assert!(set.contains(&dev));
+        assert!(!set.contains(&SemVer::new(1, 0, 0)));
+        assert_eq!(set.insert(SemVer::new(1, 0, 0)), Err(Error::Overflow("set capacity")));
+        assert_eq!(set.insert(SemVer::new(0, 9, 8)), Ok(false));
+        assert_eq!(set.max(), Some(SemVer::new(0, 9, 9)));
+        assert_eq!(set.min(), Some(SemVer::new(0, 9, 8)));
+    }
+    #[test]
+    fn test_version_set_serialization() {
+        let mut set = VersionSet::<4>::new();
+        for ver in [SemVer::new(1, 0, 0), SemVer::new(0, 9, 8)] {
+            set.insert(ver).unwrap();
+        }
+        let mut buf = [0xffu8; 64];
+        assert_eq!(set.write_to(&mut buf), Ok(32));
+        assert_eq!(VersionSet::<4>::read_from(&buf), Ok(set));
+        assert_eq!(VersionSet::<4>::read_from(&buf[..32]), Ok(set));
+        assert_eq!(VersionSet::<4>::read_from(&buf[..20]), Err(Error::Truncated));
+        assert_eq!(VersionSet::<1>::read_from(&buf), Err(Error::Overflow("set capacity")));
+        assert_eq!(set.write_to(&mut buf[..31]), Err(Error::BufferTooSmall { needed: 32 }));
+        // appended out of order, with a repeat
+        let mut log = [0xffu8; 64];
+        for (chunk, ver) in log.chunks_exact_mut(16).zip([SemVer::new(1, 0, 0), SemVer::new(0, 9, 8), SemVer::new(1, 0, 0)]) {
+            chunk.copy_from_slice(&ver.to_le_bytes());
+        }
+        assert_eq!(VersionSet::<4>::read_from(&log), Ok(set));
+    }
+}
@@ -0,0 +1,107 @@
+//! A version signed with ed25519, so a bootloader can trust the version an update
+//! advertises. Signing happens on the host; verifying works without `std`.
+//!
+//! The signature covers `SIGNING_CONTEXT` followed by the 16-byte serialization, so a
+//! signature over a version can't be replayed as a signature over some other 16-byte
+//! message. The binary form is the 16-byte serialization followed by the 64-byte
+//! signature, 80 bytes in total.
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use crate::{Error, SemVer};
+
+/// Domain separation prefix for version signatures.
+pub const SIGNING_CONTEXT: &[u8; 16] = b"xous-semver-sig1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedVersion {
+    version: SemVer,
+    signature: [u8; 64],
+}
+
+fn message(version: &SemVer) -> [u8; 32] {
+    let mut msg = [0u8; 32];
+    msg[..16].copy_from_slice(SIGNING_CONTEXT);
+    msg[16..].copy_from_slice(&<[u8; 16]>::from(version));
+    msg
+}
+
+impl SignedVersion {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = SemVer::SERIALIZED_LEN + 64;
+
+    pub fn sign(version: SemVer, key: &SigningKey) -> Self {
+        SignedVersion { version, signature: key.sign(&message(&version)).to_bytes() }
+    }
+    /// Returns the version if the signature is valid for `key`, and
+    /// `Error::BadSignature` otherwise. Verification is strict: weak keys and
+    /// malleable signatures are rejected.
+    pub fn verify(&self, key: &VerifyingKey) -> Result<SemVer, Error> {
+        key.verify_strict(&message(&self.version), &Signature::from_bytes(&self.signature))
+            .map(|_| self.version)
+            .map_err(|_| Error::BadSignature)
+    }
+    /// The version as claimed, before any check of the signature. Don't make decisions
+    /// on it; use `verify`.
+    pub fn unverified_version(&self) -> SemVer {
+        self.version
+    }
+    pub fn signature(&self) -> &[u8; 64] {
+        &self.signature
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..SignedVersion::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SignedVersion::SERIALIZED_LEN })?;
+        dest[..16].copy_from_slice(&<[u8; 16]>::from(&self.version));
+        dest[16..].copy_from_slice(&self.signature);
+        Ok(SignedVersion::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`. The signature is not checked
+    /// until `verify`.
+    pub fn read_from(buf: &[u8]) -> Result<SignedVersion, Error> {
+        let (version, rest) = buf.split_first_chunk::<16>().ok_or(Error::Truncated)?;
+        let (signature, _) = rest.split_first_chunk::<64>().ok_or(Error::Truncated)?;
+        Ok(SignedVersion {
+            version: SemVer::from_bytes_strict(version)?,
+            signature: *signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_signed_version() {
+        let key = SigningKey::from_bytes(&[7u8; 32]);
+        let other = SigningKey::from_bytes(&[8u8; 32]);
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let signed = SignedVersion::sign(ver, &key);
+        assert_eq!(signed.verify(&key.verifying_key()), Ok(ver));
+        assert_eq!(signed.verify(&other.verifying_key()), Err(Error::BadSignature));
+
+        let mut buf = [0u8; 80];
+        assert_eq!(signed.write_to(&mut buf), Ok(80));
+        let read = SignedVersion::read_from(&buf).unwrap();
+        assert_eq!(read, signed);
+        assert_eq!(read.verify(&key.verifying_key()), Ok(ver));
+
+        // bump the major version without re-signing
+        buf[0] = 1;
+        let forged = SignedVersion::read_from(&buf).unwrap();
+        assert_eq!(forged.unverified_version().maj, 1);
+        assert_eq!(forged.verify(&key.verifying_key()), Err(Error::BadSignature));
+        assert_eq!(SignedVersion::read_from(&buf[..79]), Err(Error::Truncated));
+        assert_eq!(signed.write_to(&mut buf[..79]), Err(Error::BufferTooSmall { needed: 80 }));
+
+        // the identity key accepts an identity signature over any message, unless the
+        // weak key is refused
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        let weak = VerifyingKey::from_bytes(&identity).unwrap();
+        let mut sig = [0u8; 64];
+        sig[0] = 1;
+        let forged = SignedVersion { version: ver, signature: sig };
+        assert!(ed25519_dalek::Verifier::verify(&weak, &message(&ver), &Signature::from_bytes(&sig)).is_ok());
+        assert_eq!(forged.verify(&weak), Err(Error::BadSignature));
+    }
+}
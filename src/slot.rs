@@ -0,0 +1,63 @@
+//! A/B slot selection for bootloaders that keep two images.
+use core::cmp::Ordering;
+use crate::{OrderingPolicy, SemVer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    A,
+    B,
+}
+
+/// Why `select_boot_slot` chose what it did, for the boot log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotReason {
+    /// Only the chosen slot holds a readable version.
+    OnlyValid,
+    /// Both slots are valid and the chosen one is newer under the policy.
+    Newer,
+    /// Both slots are valid and equal under the policy, so slot A wins.
+    Tie,
+    /// Neither slot holds a readable version.
+    NoValidSlot,
+}
+
+/// Picks the slot to boot given each slot's version, with `None` for a slot that is
+/// empty or whose version record failed to read. The newer slot under `policy` wins,
+/// ties go to slot A, and a single valid slot is always chosen. The slot is `None` only
+/// if neither is valid.
+pub fn select_boot_slot(
+    a: Option<SemVer>,
+    b: Option<SemVer>,
+    policy: &impl OrderingPolicy,
+) -> (Option<Slot>, SlotReason) {
+    match (a, b) {
+        (Some(a), Some(b)) => match policy.cmp(&a, &b) {
+            Ordering::Less => (Some(Slot::B), SlotReason::Newer),
+            Ordering::Greater => (Some(Slot::A), SlotReason::Newer),
+            Ordering::Equal => (Some(Slot::A), SlotReason::Tie),
+        },
+        (Some(_), None) => (Some(Slot::A), SlotReason::OnlyValid),
+        (None, Some(_)) => (Some(Slot::B), SlotReason::OnlyValid),
+        (None, None) => (None, SlotReason::NoValidSlot),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::policy::{Standard, WithCommit};
+    #[test]
+    fn test_select_boot_slot() {
+        let v = |s| Some(SemVer::from_str(s).unwrap());
+        assert_eq!(select_boot_slot(v("v0.9.8"), v("v0.9.9"), &Standard), (Some(Slot::B), SlotReason::Newer));
+        assert_eq!(select_boot_slot(v("v0.9.9-1"), v("v0.9.9"), &Standard), (Some(Slot::A), SlotReason::Newer));
+        assert_eq!(select_boot_slot(v("v0.9.9-1-g00000001"), v("v0.9.9-1-g00000002"), &Standard), (Some(Slot::A), SlotReason::Tie));
+        assert_eq!(select_boot_slot(v("v0.9.9-1-g00000001"), v("v0.9.9-1-g00000002"), &WithCommit), (Some(Slot::B), SlotReason::Newer));
+        assert_eq!(select_boot_slot(None, v("v0.1.0"), &Standard), (Some(Slot::B), SlotReason::OnlyValid));
+        assert_eq!(select_boot_slot(v("v0.1.0"), None, &Standard), (Some(Slot::A), SlotReason::OnlyValid));
+        assert_eq!(select_boot_slot(None, None, &Standard), (None, SlotReason::NoValidSlot));
+        // a slot whose record is corrupt is passed as None
+        let erased = SemVer::from_bytes_strict(&[0xff; 16]).ok();
+        assert_eq!(select_boot_slot(erased, v("v0.1.0"), &Standard), (Some(Slot::B), SlotReason::OnlyValid));
+    }
+}
@@ -0,0 +1,45 @@
+//! `proptest` strategies, so property tests in dependent crates share one set of
+//! generators.
+use proptest::prelude::*;
+use crate::{SemVer, VersionRange};
+
+/// Any version, with or without a commit.
+pub fn any_semver() -> impl Strategy<Value = SemVer> {
+    (any::<u16>(), any::<u16>(), any::<u16>(), any::<u16>(), any::<Option<u32>>())
+        .prop_map(|(maj, min, rev, extra, commit)| SemVer { maj, min, rev, extra, commit })
+}
+
+/// Tagged releases: `extra` 0 and no commit.
+pub fn release_semver() -> impl Strategy<Value = SemVer> {
+    (any::<u16>(), any::<u16>(), any::<u16>()).prop_map(|(maj, min, rev)| SemVer::new(maj, min, rev))
+}
+
+/// Versions in `range`, with or without a commit. Panics if the range is empty, like
+/// proptest's own range strategies.
+pub fn semver_in_range(range: VersionRange) -> impl Strategy<Value = SemVer> {
+    assert!(!range.is_empty(), "semver_in_range: empty range {}", range);
+    let lo = u64::from(range.min) + !range.min_inclusive as u64;
+    let hi = u64::from(range.max) - !range.max_inclusive as u64;
+    (lo..=hi, any::<Option<u32>>()).prop_map(|(packed, commit)| SemVer { commit, ..SemVer::from(packed) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    proptest! {
+        #[test]
+        fn test_any_semver_round_trips(ver in any_semver()) {
+            prop_assert!(SemVer::from_bytes_strict(&ver.to_le_bytes()).unwrap().eq_exact(&ver));
+        }
+        #[test]
+        fn test_release_semver(ver in release_semver()) {
+            prop_assert!(ver.is_release());
+        }
+        #[test]
+        fn test_semver_in_range(ver in semver_in_range(VersionRange {
+            min: SemVer::new(0, 9, 8), max: SemVer::new(0, 10, 0), min_inclusive: false, max_inclusive: false,
+        })) {
+            prop_assert!(ver > SemVer::new(0, 9, 8) && ver < SemVer::new(0, 10, 0));
+        }
+    }
+}
@@ -0,0 +1,152 @@
+//! End-of-support dates per release series, delivered inside update manifests.
+//!
+//! The binary form is a little-endian entry count, then each entry: little-endian `maj`
+//! and `min`, then the `deprecated_at` and `end_of_life` epochs as little-endian u64s,
+//! 20 bytes per entry.
+use crate::table::{Record, Table};
+use crate::{Error, SemVer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupportStatus {
+    Supported,
+    /// Still supported, but users should move to a newer series.
+    Deprecated,
+    /// No longer receives fixes.
+    EndOfLife,
+}
+
+/// The support dates of one `maj.min` series, in seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SeriesSupport {
+    pub maj: u16,
+    pub min: u16,
+    pub deprecated_at: u64,
+    pub end_of_life: u64,
+}
+
+impl SeriesSupport {
+    /// Length of the binary form.
+    pub const SERIALIZED_LEN: usize = 20;
+
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..SeriesSupport::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SeriesSupport::SERIALIZED_LEN })?;
+        dest[0..2].copy_from_slice(&self.maj.to_le_bytes());
+        dest[2..4].copy_from_slice(&self.min.to_le_bytes());
+        dest[4..12].copy_from_slice(&self.deprecated_at.to_le_bytes());
+        dest[12..20].copy_from_slice(&self.end_of_life.to_le_bytes());
+        Ok(SeriesSupport::SERIALIZED_LEN)
+    }
+    /// Reads the binary form from the start of `buf`.
+    pub fn read_from(buf: &[u8]) -> Result<SeriesSupport, Error> {
+        let (maj, rest) = buf.split_first_chunk::<2>().ok_or(Error::Truncated)?;
+        let (min, rest) = rest.split_first_chunk::<2>().ok_or(Error::Truncated)?;
+        let (deprecated_at, rest) = rest.split_first_chunk::<8>().ok_or(Error::Truncated)?;
+        let (end_of_life, _) = rest.split_first_chunk::<8>().ok_or(Error::Truncated)?;
+        let entry = SeriesSupport {
+            maj: u16::from_le_bytes(*maj),
+            min: u16::from_le_bytes(*min),
+            deprecated_at: u64::from_le_bytes(*deprecated_at),
+            end_of_life: u64::from_le_bytes(*end_of_life),
+        };
+        if entry.end_of_life < entry.deprecated_at {
+            return Err(Error::Malformed("end of life before deprecation"));
+        }
+        Ok(entry)
+    }
+}
+
+impl Record for SeriesSupport {
+    const LEN: usize = SeriesSupport::SERIALIZED_LEN;
+    fn write_record(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.write_to(buf)
+    }
+    fn read_record(buf: &[u8]) -> Result<Self, Error> {
+        SeriesSupport::read_from(buf)
+    }
+}
+
+/// The support dates of every listed series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SupportWindow<'a> {
+    entries: Table<'a, SeriesSupport>,
+}
+
+impl<'a> SupportWindow<'a> {
+    pub const fn new(entries: &'a [SeriesSupport]) -> Self {
+        SupportWindow { entries: Table::Slice(entries) }
+    }
+    /// Parses a serialized table in place. Every entry is validated up front, so
+    /// `status` cannot fail later.
+    pub fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        Ok(SupportWindow { entries: Table::read_from(buf)? })
+    }
+    /// Length of the binary form.
+    pub fn serialized_len(&self) -> usize {
+        self.entries.serialized_len()
+    }
+    /// Writes the binary form to the start of `buf`, returning the number of bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        self.entries.write_to(buf)
+    }
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The entries, in order.
+    pub fn entries(&self) -> impl Iterator<Item = SeriesSupport> + 'a {
+        self.entries.iter()
+    }
+    /// The support status of `version`'s series at time `now`, in seconds since the
+    /// Unix epoch. A series newer than every entry is supported, since the table
+    /// predates it; any other series with no entry has been dropped and is end of life.
+    pub fn status(&self, version: &SemVer, now: u64) -> SupportStatus {
+        let series = (version.maj, version.min);
+        match self.entries().find(|entry| (entry.maj, entry.min) == series) {
+            Some(entry) if now >= entry.end_of_life => SupportStatus::EndOfLife,
+            Some(entry) if now >= entry.deprecated_at => SupportStatus::Deprecated,
+            Some(_) => SupportStatus::Supported,
+            None if self.entries().all(|entry| (entry.maj, entry.min) < series) => SupportStatus::Supported,
+            None => SupportStatus::EndOfLife,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_support_window() {
+        let entries = [
+            SeriesSupport { maj: 0, min: 8, deprecated_at: 1_600_000_000, end_of_life: 1_650_000_000 },
+            SeriesSupport { maj: 0, min: 9, deprecated_at: 1_700_000_000, end_of_life: 1_750_000_000 },
+        ];
+        let window = SupportWindow::new(&entries);
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(window.status(&ver, 1_699_999_999), SupportStatus::Supported);
+        assert_eq!(window.status(&ver, 1_700_000_000), SupportStatus::Deprecated);
+        assert_eq!(window.status(&ver, 1_750_000_000), SupportStatus::EndOfLife);
+        assert_eq!(window.status(&SemVer::new(0, 8, 3), 1_700_000_000), SupportStatus::EndOfLife);
+        assert_eq!(window.status(&SemVer::new(0, 10, 0), u64::MAX), SupportStatus::Supported);
+        // series older than the table, or missing from the middle of it, were dropped
+        assert_eq!(window.status(&SemVer::new(0, 7, 0), 0), SupportStatus::EndOfLife);
+        let gap = [entries[0], SeriesSupport { maj: 0, min: 10, ..entries[1] }];
+        assert_eq!(SupportWindow::new(&gap).status(&ver, 0), SupportStatus::EndOfLife);
+        assert_eq!(SupportWindow::new(&[]).status(&ver, u64::MAX), SupportStatus::Supported);
+
+        let mut buf = [0u8; 44];
+        assert_eq!(window.serialized_len(), 44);
+        assert_eq!(window.write_to(&mut buf), Ok(44));
+        let parsed = SupportWindow::read_from(&buf).unwrap();
+        assert!(parsed.entries().eq(entries.iter().copied()));
+        assert_eq!(parsed.status(&ver, 1_700_000_000), SupportStatus::Deprecated);
+
+        assert_eq!(SupportWindow::read_from(&buf[..43]), Err(Error::Truncated));
+        assert_eq!(window.write_to(&mut buf[..43]), Err(Error::BufferTooSmall { needed: 44 }));
+        buf[4 + 12..4 + 20].fill(0);
+        assert_eq!(SupportWindow::read_from(&buf), Err(Error::Malformed("end of life before deprecation")));
+    }
+}
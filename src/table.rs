@@ -0,0 +1,65 @@
+//! The count-prefixed table of fixed-size records behind `CompatMatrix`,
+//! `SupportWindow`, `Capabilities` and `Changelog`.
+//!
+//! The binary form is a little-endian u32 record count followed by the records. A table
+//! is either a slice built in code or a serialized buffer used in place, which is
+//! validated in full when it is read so that iterating it later cannot fail.
+//! `Blocklist` has the same shape, but its entries vary in length, so it keeps its own.
+use crate::Error;
+
+/// A record with a fixed-size binary form.
+pub(crate) trait Record: Copy {
+    const LEN: usize;
+    fn write_record(&self, buf: &mut [u8]) -> Result<usize, Error>;
+    fn read_record(buf: &[u8]) -> Result<Self, Error>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Table<'a, R> {
+    Slice(&'a [R]),
+    // a serialized table whose records have all been validated
+    Serialized(&'a [u8]),
+}
+
+impl<'a, R: Record + 'a> Table<'a, R> {
+    pub(crate) fn read_from(buf: &'a [u8]) -> Result<Self, Error> {
+        let (count, rest) = buf.split_first_chunk::<4>().ok_or(Error::Truncated)?;
+        let len = usize::try_from(u32::from_le_bytes(*count)).ok()
+            .and_then(|count| count.checked_mul(R::LEN))
+            .ok_or(Error::Truncated)?;
+        let records = rest.get(..len).ok_or(Error::Truncated)?;
+        for record in records.chunks_exact(R::LEN) {
+            R::read_record(record)?;
+        }
+        Ok(Table::Serialized(records))
+    }
+    pub(crate) fn serialized_len(&self) -> usize {
+        4 + self.len() * R::LEN
+    }
+    pub(crate) fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.serialized_len();
+        let (count, rest) = buf.get_mut(..needed)
+            .and_then(|dest| dest.split_first_chunk_mut::<4>())
+            .ok_or(Error::BufferTooSmall { needed })?;
+        *count = (self.len() as u32).to_le_bytes();
+        for (record, slot) in self.iter().zip(rest.chunks_exact_mut(R::LEN)) {
+            record.write_record(slot)?;
+        }
+        Ok(needed)
+    }
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Table::Slice(records) => records.len(),
+            Table::Serialized(bytes) => bytes.len() / R::LEN,
+        }
+    }
+    /// The records, in order.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = R> + 'a {
+        let (slice, bytes) = match *self {
+            Table::Slice(records) => (records, &[][..]),
+            Table::Serialized(bytes) => (&[][..], bytes),
+        };
+        // `read_from` validated every record, so none are dropped here
+        slice.iter().copied().chain(bytes.chunks_exact(R::LEN).filter_map(|record| R::read_record(record).ok()))
+    }
+}
@@ -0,0 +1,249 @@
+//! Tag-length-value encoding of a `SemVer`.
+//!
+//! Each field is written as a one-byte tag, a one-byte length, and `length` bytes of
+//! little-endian value. Decoders skip tags they don't know, so fields can be added
+//! later without breaking parsers that are already deployed. A `TAG_END` byte (or the
+//! end of the slice) terminates the record, which lets the encoding live in a
+//! zero-padded fixed-size field.
+use crate::{Error, SemVer};
+use core::fmt;
+
+pub const TAG_END: u8 = 0x00;
+pub const TAG_MAJ: u8 = 0x01;
+pub const TAG_MIN: u8 = 0x02;
+pub const TAG_REV: u8 = 0x03;
+pub const TAG_EXTRA: u8 = 0x04;
+pub const TAG_COMMIT: u8 = 0x05;
+
+fn put(buf: &mut [u8], offset: &mut usize, tag: u8, value: &[u8]) {
+    buf[*offset] = tag;
+    buf[*offset + 1] = value.len() as u8;
+    buf[*offset + 2..*offset + 2 + value.len()].copy_from_slice(value);
+    *offset += 2 + value.len();
+}
+
+impl SemVer {
+    /// Number of bytes `encode_tlv` will write for this version.
+    pub fn tlv_len(&self) -> usize {
+        4 * (2 + 2) + if self.commit.is_some() { 2 + 4 } else { 0 }
+    }
+    /// Writes the TLV encoding into `buf`, returning the number of bytes written.
+    pub fn encode_tlv(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.tlv_len();
+        if buf.len() < needed {
+            return Err(Error::BufferTooSmall { needed });
+        }
+        let mut offset = 0;
+        put(buf, &mut offset, TAG_MAJ, &self.maj.to_le_bytes());
+        put(buf, &mut offset, TAG_MIN, &self.min.to_le_bytes());
+        put(buf, &mut offset, TAG_REV, &self.rev.to_le_bytes());
+        put(buf, &mut offset, TAG_EXTRA, &self.extra.to_le_bytes());
+        if let Some(commit) = self.commit {
+            put(buf, &mut offset, TAG_COMMIT, &commit.to_le_bytes());
+        }
+        Ok(offset)
+    }
+    /// Parses a TLV encoding. Unknown tags are skipped; `maj`, `min` and `rev` are required.
+    pub fn decode_tlv(buf: &[u8]) -> Result<SemVer, Error> {
+        let partial = Partial::decode(buf)?;
+        Ok(SemVer {
+            maj: partial.maj.ok_or(Error::Malformed("missing maj"))?,
+            min: partial.min.ok_or(Error::Malformed("missing min"))?,
+            rev: partial.rev.ok_or(Error::Malformed("missing rev"))?,
+            extra: partial.extra,
+            commit: partial.commit,
+        })
+    }
+}
+
+/// Iterates over the `(tag, value)` pairs of a TLV record, stopping at `TAG_END`.
+pub struct Fields<'a> {
+    buf: &'a [u8],
+    offset: usize,
+}
+pub fn fields(buf: &[u8]) -> Fields<'_> {
+    Fields { buf, offset: 0 }
+}
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u8, &'a [u8]), Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let tag = *self.buf.get(self.offset)?;
+        if tag == TAG_END {
+            return None;
+        }
+        let item = self.buf.get(self.offset + 1)
+            .and_then(|&len| self.buf.get(self.offset + 2..self.offset + 2 + len as usize))
+            .ok_or(Error::Truncated);
+        match item {
+            Ok(value) => {
+                self.offset += 2 + value.len();
+                Some(Ok((tag, value)))
+            }
+            Err(e) => {
+                self.offset = self.buf.len(); // don't try to resync after a truncation
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+// the known fields of a record, any of which may be absent
+#[derive(Default)]
+struct Partial {
+    maj: Option<u16>,
+    min: Option<u16>,
+    rev: Option<u16>,
+    extra: u16,
+    commit: Option<u32>,
+}
+impl Partial {
+    fn decode(buf: &[u8]) -> Result<Partial, Error> {
+        let mut partial = Partial::default();
+        for field in fields(buf) {
+            partial.apply(field?)?;
+        }
+        Ok(partial)
+    }
+    // returns `Ok(false)` for a tag this version of the crate does not know
+    fn apply(&mut self, (tag, value): (u8, &[u8])) -> Result<bool, Error> {
+        match tag {
+            TAG_MAJ => self.maj = Some(u16_field(value)?),
+            TAG_MIN => self.min = Some(u16_field(value)?),
+            TAG_REV => self.rev = Some(u16_field(value)?),
+            TAG_EXTRA => self.extra = u16_field(value)?,
+            TAG_COMMIT => self.commit = Some(u32::from_le_bytes(
+                value.try_into().map_err(|_| Error::Malformed("bad commit length"))?
+            )),
+            _ => return Ok(false), // a field from a newer writer
+        }
+        Ok(true)
+    }
+}
+
+/// Renders a TLV record, failing with `Error::UnknownField` if it carries any field this
+/// version of the crate cannot interpret.
+pub fn try_display(buf: &[u8]) -> Result<StrictDisplay, Error> {
+    let mut partial = Partial::default();
+    for field in fields(buf) {
+        let field = field?;
+        if !partial.apply(field)? {
+            return Err(Error::UnknownField(field.0));
+        }
+    }
+    SemVer::decode_tlv(buf).map(StrictDisplay)
+}
+/// A TLV record that `try_display` understood in full; displays as the `SemVer` would.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StrictDisplay(SemVer);
+impl StrictDisplay {
+    pub fn version(&self) -> SemVer {
+        self.0
+    }
+}
+impl fmt::Display for StrictDisplay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Renders whatever can be understood of a TLV record. Missing components print as `?`,
+/// unknown fields are listed as `[?tag]`, and undecodable data as `[corrupt]`.
+pub fn display_lossy(buf: &[u8]) -> LossyDisplay<'_> {
+    LossyDisplay(buf)
+}
+pub struct LossyDisplay<'a>(&'a [u8]);
+impl fmt::Display for LossyDisplay<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct Part(Option<u16>);
+        impl fmt::Display for Part {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    Some(v) => write!(f, "{}", v),
+                    None => write!(f, "?"),
+                }
+            }
+        }
+        let mut partial = Partial::default();
+        let mut corrupt = false;
+        for field in fields(self.0) {
+            match field.and_then(|field| partial.apply(field)) {
+                Ok(_) => {}
+                Err(_) => corrupt = true,
+            }
+        }
+        write!(f, "v{}.{}.{}-{}", Part(partial.maj), Part(partial.min), Part(partial.rev), partial.extra)?;
+        if let Some(commit) = partial.commit {
+            write!(f, "-g{:x}", commit)?;
+        }
+        for (tag, _) in fields(self.0).flatten() {
+            if !matches!(tag, TAG_MAJ | TAG_MIN | TAG_REV | TAG_EXTRA | TAG_COMMIT) {
+                write!(f, " [?{:02x}]", tag)?;
+            }
+        }
+        if corrupt {
+            write!(f, " [corrupt]")?;
+        }
+        Ok(())
+    }
+}
+
+fn u16_field(value: &[u8]) -> Result<u16, Error> {
+    Ok(u16::from_le_bytes(value.try_into().map_err(|_| Error::Malformed("bad u16 field length"))?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_tlv() {
+        let mut buf = [0u8; 32];
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.encode_tlv(&mut buf), Ok(22));
+        assert_eq!(
+            buf[..22],
+            [1, 2, 0, 0,
+            2, 2, 9, 0,
+            3, 2, 8, 0,
+            4, 2, 248, 2,
+            5, 4, 0x34, 0x12, 0xcd, 0xab]
+        );
+        // zero padding after the record acts as a terminator
+        assert_eq!(SemVer::decode_tlv(&buf), Ok(ver));
+
+        let ver = SemVer::from_str("v1.2.3").unwrap();
+        assert_eq!(ver.encode_tlv(&mut buf[..15]), Err(Error::BufferTooSmall { needed: 16 }));
+        let len = ver.encode_tlv(&mut buf).unwrap();
+        assert_eq!(SemVer::decode_tlv(&buf[..len]), Ok(ver));
+
+        // unknown tags from a newer writer are skipped
+        let newer = [0x40, 3, 0xaa, 0xbb, 0xcc,
+            1, 2, 1, 0,
+            2, 2, 2, 0,
+            0x41, 0,
+            3, 2, 3, 0];
+        assert_eq!(SemVer::decode_tlv(&newer).unwrap(), SemVer::from_str("v1.2.3").unwrap());
+
+        assert_eq!(SemVer::decode_tlv(&[1, 2, 1]), Err(Error::Truncated));
+        assert_eq!(SemVer::decode_tlv(&[1]), Err(Error::Truncated));
+        assert_eq!(SemVer::decode_tlv(&[1, 1, 1]), Err(Error::Malformed("bad u16 field length")));
+        assert_eq!(SemVer::decode_tlv(&[1, 2, 1, 0, 2, 2, 2, 0]), Err(Error::Malformed("missing rev")));
+    }
+    #[test]
+    fn test_tlv_display() {
+        let mut buf = [0u8; 32];
+        let len = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap().encode_tlv(&mut buf).unwrap();
+        assert_eq!(try_display(&buf).unwrap().to_string(), "v0.9.8-760-gabcd1234");
+        assert_eq!(format!("{:#}", try_display(&[1, 2, 1, 0, 2, 2, 2, 0, 3, 2, 3, 0]).unwrap()), "v1.2.3");
+        assert_eq!(try_display(&buf).map(|d| d.version()), SemVer::decode_tlv(&buf));
+        assert_eq!(display_lossy(&buf).to_string(), "v0.9.8-760-gabcd1234");
+
+        // a newer writer appended a field we don't know about
+        buf[len..len + 3].copy_from_slice(&[0x40, 1, 0x03]);
+        assert_eq!(try_display(&buf), Err(Error::UnknownField(0x40)));
+        assert_eq!(display_lossy(&buf).to_string(), "v0.9.8-760-gabcd1234 [?40]");
+
+        assert_eq!(display_lossy(&[1, 2, 1, 0, 3, 2, 3, 0]).to_string(), "v1.?.3-0");
+        assert_eq!(display_lossy(&[1, 2, 1, 0, 2, 2, 2]).to_string(), "v1.?.?-0 [corrupt]");
+        assert_eq!(display_lossy(&[1, 1, 1, 2, 2, 2, 0]).to_string(), "v?.2.?-0 [corrupt]");
+    }
+}
@@ -0,0 +1,131 @@
+//! Conversions between `SemVer` and plain tuples of its numeric components.
+use crate::{Error, SemVer};
+
+impl From<(u16, u16, u16)> for SemVer {
+    fn from((maj, min, rev): (u16, u16, u16)) -> SemVer {
+        SemVer::new(maj, min, rev)
+    }
+}
+
+// (maj, min, rev, extra): the layout of a Windows FILEVERSION/PRODUCTVERSION resource,
+// with `extra` in the fourth (build) slot. The commit has no place in it and is dropped.
+impl From<SemVer> for (u16, u16, u16, u16) {
+    fn from(ver: SemVer) -> Self {
+        (ver.maj, ver.min, ver.rev, ver.extra)
+    }
+}
+impl From<(u16, u16, u16, u16)> for SemVer {
+    fn from((maj, min, rev, extra): (u16, u16, u16, u16)) -> SemVer {
+        SemVer { extra, ..SemVer::new(maj, min, rev) }
+    }
+}
+
+// host tools often carry versions as wider integers; these check each component
+// rather than truncating, and name the first one that doesn't fit
+fn narrow<T: TryInto<u16>>(v: T, component: &'static str) -> Result<u16, Error> {
+    v.try_into().map_err(|_| Error::Overflow(component))
+}
+
+macro_rules! try_from_wide {
+    ($($t:ty),*) => {$(
+        impl TryFrom<($t, $t, $t)> for SemVer {
+            type Error = Error;
+            fn try_from((maj, min, rev): ($t, $t, $t)) -> Result<SemVer, Error> {
+                Ok(SemVer::new(narrow(maj, "maj")?, narrow(min, "min")?, narrow(rev, "rev")?))
+            }
+        }
+        impl TryFrom<($t, $t, $t, $t)> for SemVer {
+            type Error = Error;
+            fn try_from((maj, min, rev, extra): ($t, $t, $t, $t)) -> Result<SemVer, Error> {
+                let extra = narrow(extra, "extra")?;
+                Ok(SemVer { extra, ..SemVer::try_from((maj, min, rev))? })
+            }
+        }
+        impl TryFrom<[$t; 3]> for SemVer {
+            type Error = Error;
+            fn try_from([maj, min, rev]: [$t; 3]) -> Result<SemVer, Error> {
+                SemVer::try_from((maj, min, rev))
+            }
+        }
+        impl TryFrom<[$t; 4]> for SemVer {
+            type Error = Error;
+            fn try_from([maj, min, rev, extra]: [$t; 4]) -> Result<SemVer, Error> {
+                SemVer::try_from((maj, min, rev, extra))
+            }
+        }
+    )*};
+}
+try_from_wide!(u32, u64, usize);
+
+impl From<SemVer> for [u16; 4] {
+    fn from(ver: SemVer) -> Self {
+        ver.as_array()
+    }
+}
+impl From<[u16; 4]> for SemVer {
+    fn from([maj, min, rev, extra]: [u16; 4]) -> SemVer {
+        SemVer::from((maj, min, rev, extra))
+    }
+}
+
+impl SemVer {
+    /// Names of the numeric components, in the order `as_array` and `components` give
+    /// them, for table headers and diff output.
+    pub const COMPONENT_NAMES: [&'static str; 4] = ["maj", "min", "rev", "extra"];
+
+    /// The numeric components `[maj, min, rev, extra]`; the commit is left out.
+    pub const fn as_array(&self) -> [u16; 4] {
+        [self.maj, self.min, self.rev, self.extra]
+    }
+    /// Iterates over the numeric components in `as_array` order.
+    pub fn components(&self) -> core::array::IntoIter<u16, 4> {
+        self.as_array().into_iter()
+    }
+    /// The `FILEVERSION` quadruple `maj, min, rev, extra` for Windows resource scripts.
+    pub fn file_version(&self) -> (u16, u16, u16, u16) {
+        (*self).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_file_version() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.file_version(), (0, 9, 8, 760));
+        assert_eq!(SemVer::from(ver.file_version()), SemVer::from_str("v0.9.8-760").unwrap());
+        let quad: (u16, u16, u16, u16) = SemVer::from_str("v1.2.3").unwrap().into();
+        assert_eq!(quad, (1, 2, 3, 0));
+    }
+    #[test]
+    fn test_new() {
+        const MIN_EC: SemVer = SemVer::new(0, 9, 6);
+        assert_eq!(MIN_EC, SemVer::from_str("v0.9.6").unwrap());
+        assert_eq!(SemVer::from((0, 9, 6)), MIN_EC);
+        assert_eq!(SemVer::from((0, 9, 6, 12)), SemVer::from_str("v0.9.6-12").unwrap());
+    }
+    #[test]
+    fn test_try_from_wide() {
+        assert_eq!(SemVer::try_from((0u32, 9, 8)), Ok(SemVer::new(0, 9, 8)));
+        assert_eq!(SemVer::try_from([0u64, 9, 8, 760]), Ok(SemVer::from_str("v0.9.8-760").unwrap()));
+        assert_eq!(SemVer::try_from((1u32, 65536, 0)), Err(Error::Overflow("min")));
+        assert_eq!(SemVer::try_from([1usize, 2, 3, 70000]), Err(Error::Overflow("extra")));
+        // the first component out of range is the one reported
+        assert_eq!(SemVer::try_from((u64::MAX, u64::MAX, 0, 0)), Err(Error::Overflow("maj")));
+    }
+    #[test]
+    fn test_components() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.as_array(), [0, 9, 8, 760]);
+        assert_eq!(<[u16; 4]>::from(ver), [0, 9, 8, 760]);
+        assert_eq!(SemVer::from([0, 9, 8, 760]), SemVer::from_str("v0.9.8-760").unwrap());
+        let newer = SemVer::from_str("v0.9.9-2").unwrap();
+        let changed: Vec<_> = SemVer::COMPONENT_NAMES.iter()
+            .zip(ver.components().zip(newer.components()))
+            .filter(|(_, (a, b))| a != b)
+            .map(|(name, _)| *name)
+            .collect();
+        assert_eq!(changed, ["rev", "extra"]);
+    }
+}
@@ -0,0 +1,213 @@
+//! The version as a UF2 extension tag, for the factory flasher.
+//!
+//! A UF2 block is 512 bytes: a 32-byte header, up to 476 bytes of payload, and a final
+//! magic word. When flag `0x8000` is set, extension tags follow the payload at the next
+//! 4-byte boundary. Each tag is a size byte (covering the 4-byte header), a 3-byte
+//! little-endian type and the data, padded to 4 bytes; a zero-size tag ends the list.
+//! The version tag carries the 16-byte serialization under type `UF2_VERSION_TAG`.
+use crate::{Error, SemVer};
+
+/// Length of a UF2 block.
+pub const UF2_BLOCK_LEN: usize = 512;
+/// The extension tag type for the version; chosen at random, as the UF2 spec asks.
+pub const UF2_VERSION_TAG: u32 = 0x00c3_5e7a;
+
+const MAGIC_START0: u32 = 0x0a32_4655;
+const MAGIC_START1: u32 = 0x9e5d_5157;
+const MAGIC_END: u32 = 0x0ab1_6f30;
+const FLAG_EXTENSION_TAGS: u32 = 0x8000;
+const TAG_LEN: usize = 4 + SemVer::SERIALIZED_LEN;
+
+// every offset read is within the block, so the zero fallback is never taken
+fn word(block: &[u8; UF2_BLOCK_LEN], at: usize) -> u32 {
+    block.get(at..).and_then(|rest| rest.first_chunk()).map_or(0, |w| u32::from_le_bytes(*w))
+}
+
+// checks the magic numbers and returns the offset of the first extension tag, or None
+// if the block has no tags
+fn tags_start(block: &[u8; UF2_BLOCK_LEN]) -> Result<Option<usize>, Error> {
+    if word(block, 0) != MAGIC_START0 || word(block, 4) != MAGIC_START1 || word(block, 508) != MAGIC_END {
+        return Err(Error::Malformed("bad UF2 magic"));
+    }
+    let payload = word(block, 16) as usize;
+    if payload > 476 {
+        return Err(Error::Malformed("UF2 payload too large"));
+    }
+    Ok((word(block, 8) & FLAG_EXTENSION_TAGS != 0).then(|| (32 + payload + 3) & !3))
+}
+
+// walks the tag list from `at`, returning the offset of the terminator and the version
+// tag's data offset if there is one
+fn walk_tags(block: &[u8; UF2_BLOCK_LEN], mut at: usize) -> Result<(usize, Option<usize>), Error> {
+    let mut version = None;
+    while at + 4 <= 508 {
+        let size = block[at] as usize;
+        if size == 0 {
+            return Ok((at, version));
+        }
+        if size < 4 || at + size > 508 {
+            return Err(Error::Malformed("bad UF2 extension tag"));
+        }
+        if word(block, at) >> 8 == UF2_VERSION_TAG {
+            if size != TAG_LEN {
+                return Err(Error::Malformed("version tag has the wrong size"));
+            }
+            version = Some(at + 4);
+        }
+        at += (size + 3) & !3;
+    }
+    // a list that runs into the end magic has no room for a terminator
+    Ok((at, version))
+}
+
+impl SemVer {
+    /// Appends the version tag to a UF2 block's extension tags, setting the tags flag.
+    /// An existing version tag is overwritten instead. Fails with `Overflow("UF2 block")`
+    /// if the payload and existing tags leave no room.
+    pub fn write_uf2_tag(&self, block: &mut [u8; UF2_BLOCK_LEN]) -> Result<(), Error> {
+        let start = match tags_start(block)? {
+            Some(start) => start,
+            None => {
+                let payload = word(block, 16) as usize;
+                let start = (32 + payload + 3) & !3;
+                // no tags yet, so start an empty list
+                if start + 4 <= 508 {
+                    block[start..start + 4].fill(0);
+                }
+                start
+            }
+        };
+        let (end, existing) = walk_tags(block, start)?;
+        let ser = <[u8; 16]>::from(self);
+        if let Some(data) = existing {
+            block[data..data + 16].copy_from_slice(&ser);
+        } else {
+            if end + TAG_LEN + 4 > 508 {
+                return Err(Error::Overflow("UF2 block"));
+            }
+            block[end..end + 4].copy_from_slice(&((UF2_VERSION_TAG << 8) | TAG_LEN as u32).to_le_bytes());
+            block[end + 4..end + TAG_LEN].copy_from_slice(&ser);
+            block[end + TAG_LEN..end + TAG_LEN + 4].fill(0);
+        }
+        let flags = word(block, 8) | FLAG_EXTENSION_TAGS;
+        block[8..12].copy_from_slice(&flags.to_le_bytes());
+        Ok(())
+    }
+    /// Reads the version tag from a UF2 block, if it has one.
+    pub fn read_uf2_tag(block: &[u8; UF2_BLOCK_LEN]) -> Result<Option<SemVer>, Error> {
+        let Some(start) = tags_start(block)? else {
+            return Ok(None);
+        };
+        match walk_tags(block, start)?.1 {
+            Some(data) => {
+                let ser = block.get(data..).and_then(|rest| rest.first_chunk()).ok_or(Error::Truncated)?;
+                SemVer::from_bytes_strict(ser).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+/// Reads the version from a whole UF2 image. Every block that carries a version tag must
+/// carry the same version; blocks without one are skipped.
+pub fn uf2_version(image: &[u8]) -> Result<Option<SemVer>, Error> {
+    let (blocks, remainder) = image.as_chunks::<UF2_BLOCK_LEN>();
+    if !remainder.is_empty() {
+        return Err(Error::Truncated);
+    }
+    let mut found: Option<SemVer> = None;
+    for block in blocks {
+        if let Some(ver) = SemVer::read_uf2_tag(block)? {
+            match found {
+                Some(prev) if !prev.eq_exact(&ver) => return Err(Error::Malformed("UF2 blocks disagree on the version")),
+                _ => found = Some(ver),
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Reads the version from the UF2 file at `path`. Format errors are reported as
+/// `io::ErrorKind::InvalidData` wrapping the `Error`.
+#[cfg(feature="std")]
+pub fn read_uf2_file(path: impl AsRef<std::path::Path>) -> std::io::Result<Option<SemVer>> {
+    let image = std::fs::read(path)?;
+    uf2_version(&image).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    fn block(payload: u32) -> [u8; UF2_BLOCK_LEN] {
+        let mut block = [0u8; UF2_BLOCK_LEN];
+        block[0..4].copy_from_slice(&MAGIC_START0.to_le_bytes());
+        block[4..8].copy_from_slice(&MAGIC_START1.to_le_bytes());
+        block[16..20].copy_from_slice(&payload.to_le_bytes());
+        block[508..512].copy_from_slice(&MAGIC_END.to_le_bytes());
+        block[32..32 + payload as usize].fill(0xaa);
+        block
+    }
+    #[test]
+    fn test_uf2_tag() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let mut b = block(256);
+        assert_eq!(SemVer::read_uf2_tag(&b), Ok(None));
+        ver.write_uf2_tag(&mut b).unwrap();
+        assert_eq!(word(&b, 8), FLAG_EXTENSION_TAGS);
+        assert_eq!(b[288..292], [20, 0x7a, 0x5e, 0xc3]);
+        assert_eq!(b[308..312], [0; 4]);
+        assert_eq!(SemVer::read_uf2_tag(&b), Ok(Some(ver)));
+        assert!(b[32..288].iter().all(|&x| x == 0xaa));
+
+        // rewriting replaces the tag rather than adding another
+        let newer = SemVer::new(1, 0, 0);
+        newer.write_uf2_tag(&mut b).unwrap();
+        assert_eq!(SemVer::read_uf2_tag(&b), Ok(Some(newer)));
+        assert_eq!(b[308..312], [0; 4]);
+
+        // other tags are kept and skipped over
+        let mut tagged = block(3);
+        tagged[8..12].copy_from_slice(&FLAG_EXTENSION_TAGS.to_le_bytes());
+        tagged[36..44].copy_from_slice(&[7, 0xbc, 0xc7, 0x9f, b'1', b'.', b'0', 0]);
+        ver.write_uf2_tag(&mut tagged).unwrap();
+        assert_eq!(tagged[36..40], [7, 0xbc, 0xc7, 0x9f]);
+        assert_eq!(tagged[44], 20);
+        assert_eq!(SemVer::read_uf2_tag(&tagged), Ok(Some(ver)));
+
+        let mut full = block(476);
+        assert_eq!(ver.write_uf2_tag(&mut full), Err(Error::Overflow("UF2 block")));
+        full[0] = 0;
+        assert_eq!(SemVer::read_uf2_tag(&full), Err(Error::Malformed("bad UF2 magic")));
+    }
+    #[test]
+    fn test_uf2_image() {
+        let ver = SemVer::from_str("v0.9.8").unwrap();
+        let mut image = [0u8; 3 * UF2_BLOCK_LEN];
+        for (i, chunk) in image.chunks_exact_mut(UF2_BLOCK_LEN).enumerate() {
+            let mut b = block(256);
+            if i != 1 {
+                ver.write_uf2_tag(&mut b).unwrap();
+            }
+            chunk.copy_from_slice(&b);
+        }
+        assert_eq!(uf2_version(&image), Ok(Some(ver)));
+        assert_eq!(uf2_version(&image[..1000]), Err(Error::Truncated));
+        let mut other = block(256);
+        SemVer::new(0, 9, 9).write_uf2_tag(&mut other).unwrap();
+        image[UF2_BLOCK_LEN..2 * UF2_BLOCK_LEN].copy_from_slice(&other);
+        assert_eq!(uf2_version(&image), Err(Error::Malformed("UF2 blocks disagree on the version")));
+    }
+    #[test]
+    #[cfg(feature="std")]
+    fn test_uf2_file() {
+        let ver = SemVer::from_str("v0.9.8").unwrap();
+        let mut image = block(256);
+        ver.write_uf2_tag(&mut image).unwrap();
+        let path = std::env::temp_dir().join(format!("xous-semver-uf2-{}.uf2", std::process::id()));
+        std::fs::write(&path, image).unwrap();
+        assert_eq!(read_uf2_file(&path).unwrap(), Some(ver));
+        std::fs::write(&path, &image[..100]).unwrap();
+        assert_eq!(read_uf2_file(&path).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
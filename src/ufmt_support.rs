@@ -0,0 +1,53 @@
+//! Rendering through `ufmt`, which avoids pulling `core::fmt` into the smallest targets.
+//! The output is identical to the `Display` impl.
+use ufmt::{uDebug, uDisplay, uWrite, Formatter};
+use crate::SemVer;
+
+impl SemVer {
+    pub fn uwrite<W: uWrite + ?Sized>(&self, w: &mut W) -> Result<(), W::Error> {
+        self.display_pieces(|piece| w.write_str(piece))
+    }
+}
+
+impl uDisplay for SemVer {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        self.display_pieces(|piece| f.write_str(piece))
+    }
+}
+/// Same as `uDisplay`; the struct form of `Debug` is not worth its code size here.
+impl uDebug for SemVer {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error> {
+        self.display_pieces(|piece| f.write_str(piece))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ufmt::uwrite;
+    struct Buf(String);
+    impl uWrite for Buf {
+        type Error = ();
+        fn write_str(&mut self, s: &str) -> Result<(), ()> {
+            self.0.push_str(s);
+            Ok(())
+        }
+    }
+    #[test]
+    fn test_uwrite() {
+        for s in ["v0.9.8-760-gabcd1234", "v0.9.8-760", "v0.0.0-0", "v65535.10.100-1000-g123abc", "v1.2.3-0-g0"] {
+            let ver = SemVer::from_str(s).unwrap();
+            let mut buf = Buf(String::new());
+            ver.uwrite(&mut buf).unwrap();
+            assert_eq!(buf.0, s);
+            assert_eq!(buf.0, ver.to_string());
+        }
+    }
+    #[test]
+    fn test_udisplay() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let mut buf = Buf(String::new());
+        uwrite!(buf, "ec {} / {:?}", ver, ver).unwrap();
+        assert_eq!(buf.0, "ec v0.9.8-760-gabcd1234 / v0.9.8-760-gabcd1234");
+    }
+}
@@ -0,0 +1,93 @@
+//! Pluggable rules for accepting an update, shared by the OTA and USB update paths.
+use crate::SemVer;
+
+/// The outcome of `UpdatePolicy::evaluate`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    Accept,
+    /// The update is refused, with a reason suitable for the update log.
+    Reject(&'static str),
+}
+
+impl Decision {
+    pub fn is_accept(&self) -> bool {
+        *self == Decision::Accept
+    }
+}
+
+/// A rule deciding whether `candidate` may replace `current`. Closures
+/// `Fn(&SemVer, &SemVer) -> Decision` are policies too.
+pub trait UpdatePolicy {
+    fn evaluate(&self, current: &SemVer, candidate: &SemVer) -> Decision;
+}
+impl<F: Fn(&SemVer, &SemVer) -> Decision> UpdatePolicy for F {
+    fn evaluate(&self, current: &SemVer, candidate: &SemVer) -> Decision {
+        self(current, candidate)
+    }
+}
+
+/// Rejects candidates older than the running version. Reinstalling the same version is
+/// allowed; commits are not compared.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ForbidDowngrade;
+impl UpdatePolicy for ForbidDowngrade {
+    fn evaluate(&self, current: &SemVer, candidate: &SemVer) -> Decision {
+        if candidate < current { Decision::Reject("downgrade") } else { Decision::Accept }
+    }
+}
+
+/// Rejects candidates with a different major version, which may change on-flash formats.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SameMajorOnly;
+impl UpdatePolicy for SameMajorOnly {
+    fn evaluate(&self, current: &SemVer, candidate: &SemVer) -> Decision {
+        if candidate.maj != current.maj { Decision::Reject("major version change") } else { Decision::Accept }
+    }
+}
+
+/// Rejects dev builds; only tagged releases may be installed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RequireRelease;
+impl UpdatePolicy for RequireRelease {
+    fn evaluate(&self, _current: &SemVer, candidate: &SemVer) -> Decision {
+        if candidate.is_release() { Decision::Accept } else { Decision::Reject("not a release build") }
+    }
+}
+
+/// Accepts only if every policy accepts, reporting the first rejection in order.
+#[derive(Clone, Copy)]
+pub struct AllOf<'a>(pub &'a [&'a dyn UpdatePolicy]);
+impl UpdatePolicy for AllOf<'_> {
+    fn evaluate(&self, current: &SemVer, candidate: &SemVer) -> Decision {
+        self.0.iter()
+            .map(|policy| policy.evaluate(current, candidate))
+            .find(|decision| !decision.is_accept())
+            .unwrap_or(Decision::Accept)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_update_policies() {
+        let v = |s| SemVer::from_str(s).unwrap();
+        let current = v("v0.9.8-10-gabcd1234");
+        assert_eq!(ForbidDowngrade.evaluate(&current, &v("v0.9.8-9")), Decision::Reject("downgrade"));
+        assert!(ForbidDowngrade.evaluate(&current, &v("v0.9.8-10")).is_accept());
+        assert_eq!(SameMajorOnly.evaluate(&current, &v("v1.0.0")), Decision::Reject("major version change"));
+        assert!(SameMajorOnly.evaluate(&current, &v("v0.1.0")).is_accept());
+        assert_eq!(RequireRelease.evaluate(&current, &v("v0.9.9-1")), Decision::Reject("not a release build"));
+        assert!(RequireRelease.evaluate(&current, &v("v0.9.9")).is_accept());
+
+        let no_patch_zero = |_: &SemVer, candidate: &SemVer| {
+            if candidate.rev == 0 { Decision::Reject("x.y.0 is a preview") } else { Decision::Accept }
+        };
+        let ota = AllOf(&[&ForbidDowngrade, &SameMajorOnly, &RequireRelease, &no_patch_zero]);
+        assert!(ota.evaluate(&current, &v("v0.9.9")).is_accept());
+        assert_eq!(ota.evaluate(&current, &v("v0.9.7")), Decision::Reject("downgrade"));
+        assert_eq!(ota.evaluate(&current, &v("v1.0.1")), Decision::Reject("major version change"));
+        assert_eq!(ota.evaluate(&current, &v("v0.10.0")), Decision::Reject("x.y.0 is a preview"));
+        assert!(AllOf(&[]).evaluate(&current, &v("v0.0.1")).is_accept());
+    }
+}
@@ -0,0 +1,54 @@
+//! USB descriptor helpers.
+use crate::{Error, SemVer};
+
+impl SemVer {
+    /// Encodes the version as a USB `bcdDevice` value, `0xJJMN` for `JJ.M.N`.
+    ///
+    /// BCD only has room for two major digits and one digit each for minor and rev, so
+    /// the version saturates: a `maj` above 99 encodes as 99.9.9, and a `min` above 9 as
+    /// `maj`.9.9, with `rev` above 9 becoming 9. Saturating the whole version rather than
+    /// each digit keeps newer firmware from reporting a lower `bcdDevice` than older
+    /// firmware. `extra` and the commit are not represented.
+    pub fn to_bcd_device(&self) -> u16 {
+        let (maj, min, rev) = if self.maj > 99 {
+            (99, 9, 9)
+        } else if self.min > 9 {
+            (self.maj, 9, 9)
+        } else {
+            (self.maj, self.min, self.rev.min(9))
+        };
+        (maj / 10) << 12 | (maj % 10) << 8 | min << 4 | rev
+    }
+    /// Decodes a `bcdDevice` value into `JJ.M.N` with `extra` 0 and no commit.
+    pub fn from_bcd_device(bcd: u16) -> Result<SemVer, Error> {
+        let nibbles = [bcd >> 12, (bcd >> 8) & 0xf, (bcd >> 4) & 0xf, bcd & 0xf];
+        if nibbles.iter().any(|&n| n > 9) {
+            return Err(Error::Malformed("invalid BCD digit"));
+        }
+        Ok(SemVer {
+            maj: nibbles[0] * 10 + nibbles[1],
+            min: nibbles[2],
+            rev: nibbles[3],
+            extra: 0,
+            commit: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_bcd_device() {
+        assert_eq!(SemVer::from_str("v0.9.8-760-gabcd1234").unwrap().to_bcd_device(), 0x0098);
+        assert_eq!(SemVer::from_str("v12.3.4").unwrap().to_bcd_device(), 0x1234);
+        assert_eq!(SemVer::from_str("v123.10.4").unwrap().to_bcd_device(), 0x9999);
+        let bcd = |s| SemVer::from_str(s).unwrap().to_bcd_device();
+        assert!(bcd("v0.10.0") >= bcd("v0.9.9"));
+        assert!(bcd("v100.0.0") >= bcd("v99.9.9"));
+        assert!(bcd("v1.2.10") >= bcd("v1.2.9"));
+        assert_eq!(SemVer::from_bcd_device(0x1234).unwrap(), SemVer::from_str("v12.3.4").unwrap());
+        assert_eq!(SemVer::from_bcd_device(0x0098).unwrap(), SemVer::from_str("v0.9.8").unwrap());
+        assert_eq!(SemVer::from_bcd_device(0x00a0), Err(Error::Malformed("invalid BCD digit")));
+    }
+}
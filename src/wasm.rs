@@ -0,0 +1,78 @@
+//! wasm-bindgen exports for the WebUSB updater, so the browser uses this crate's parsing,
+//! ordering and formatting instead of a JavaScript reimplementation.
+//!
+//! ```js
+//! const ver = SemVer.parse("v0.9.8-760-gabcd1234");
+//! if (ver.compare(SemVer.parse("v0.9.9")) < 0) { ... }
+//! ver.format(); // "v0.9.8-760-gabcd1234"
+//! ```
+use wasm_bindgen::prelude::*;
+use crate::SemVer;
+
+#[wasm_bindgen(js_name = SemVer)]
+pub struct WasmSemVer(SemVer);
+
+#[wasm_bindgen(js_class = SemVer)]
+impl WasmSemVer {
+    /// Parses a version string, throwing the parse error message on failure.
+    pub fn parse(s: &str) -> Result<WasmSemVer, String> {
+        SemVer::from_str(s).map(WasmSemVer).map_err(|e| e.to_string())
+    }
+    /// Rebuilds a version from its 16-byte serialization (e.g. read over WebUSB).
+    #[wasm_bindgen(js_name = fromBytes)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<WasmSemVer, String> {
+        SemVer::read_from(bytes).map(WasmSemVer).map_err(|e| e.to_string())
+    }
+    pub fn format(&self) -> String {
+        self.0.to_string()
+    }
+    /// Returns -1, 0 or 1. As with `Ord`, the commit is not considered.
+    pub fn compare(&self, other: &WasmSemVer) -> i32 {
+        self.0.cmp(&other.0) as i32
+    }
+    /// Exact equality, including the commit.
+    pub fn equals(&self, other: &WasmSemVer) -> bool {
+        self.0 == other.0
+    }
+    #[wasm_bindgen(js_name = toBytes)]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        <[u8; 16]>::from(self.0).to_vec()
+    }
+    #[wasm_bindgen(getter)]
+    pub fn maj(&self) -> u16 {
+        self.0.maj
+    }
+    #[wasm_bindgen(getter)]
+    pub fn min(&self) -> u16 {
+        self.0.min
+    }
+    #[wasm_bindgen(getter)]
+    pub fn rev(&self) -> u16 {
+        self.0.rev
+    }
+    #[wasm_bindgen(getter)]
+    pub fn extra(&self) -> u16 {
+        self.0.extra
+    }
+    #[wasm_bindgen(getter)]
+    pub fn commit(&self) -> Option<u32> {
+        self.0.commit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_wasm_api() {
+        let ver = WasmSemVer::parse("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(ver.format(), "v0.9.8-760-gabcd1234");
+        assert_eq!((ver.maj(), ver.min(), ver.rev(), ver.extra(), ver.commit()), (0, 9, 8, 760, Some(0xabcd1234)));
+        let newer = WasmSemVer::parse("v0.9.9").unwrap();
+        assert_eq!(ver.compare(&newer), -1);
+        assert_eq!(newer.compare(&ver), 1);
+        assert!(ver.equals(&WasmSemVer::from_bytes(&ver.to_bytes()).unwrap()));
+        assert_eq!(WasmSemVer::parse("0.9.8").err().unwrap(), "semver does not start with 'v'!");
+        assert_eq!(WasmSemVer::from_bytes(&[0; 3]).err().unwrap(), "data is truncated");
+    }
+}
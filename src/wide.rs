@@ -0,0 +1,197 @@
+//! A version with 32-bit components, for projects whose `extra` (commits since the tag)
+//! outgrows u16 on long-lived branches.
+use core::cmp::Ordering;
+use core::fmt;
+use crate::{CommitStr, Error, ParseError, SemVer};
+
+/// `SemVer` with u32 components. It has its own 24-byte serialization, laid out like
+/// `SemVer`'s with every component widened to a word.
+#[derive(Eq, Debug, Clone, Copy, Default)]
+#[cfg_attr(feature="serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SemVer32 {
+    // the same aliases as `SemVer`
+    #[cfg_attr(feature="serde", serde(alias = "major"))]
+    pub maj: u32,
+    #[cfg_attr(feature="serde", serde(alias = "minor"))]
+    pub min: u32,
+    #[cfg_attr(feature="serde", serde(alias = "patch"))]
+    pub rev: u32,
+    #[cfg_attr(feature="serde", serde(default))]
+    pub extra: u32,
+    #[cfg_attr(feature="serde", serde(default, with = "crate::serde_support::commit"))]
+    pub commit: Option<u32>,
+}
+
+impl SemVer32 {
+    /// Length of the fixed binary serialization.
+    pub const SERIALIZED_LEN: usize = 24;
+
+    pub const fn new(maj: u32, min: u32, rev: u32) -> SemVer32 {
+        SemVer32 { maj, min, rev, extra: 0, commit: None }
+    }
+    /// The commit in lowercase hex, as `SemVer::commit_str`.
+    pub fn commit_str(&self) -> Option<CommitStr> {
+        Some(CommitStr::new(self.commit?, 0))
+    }
+    /// Writes the 24-byte serialization to the start of `buf`, returning the number of
+    /// bytes written.
+    pub fn write_to(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..SemVer32::SERIALIZED_LEN)
+            .ok_or(Error::BufferTooSmall { needed: SemVer32::SERIALIZED_LEN })?;
+        let words = [self.maj, self.min, self.rev, self.extra, self.commit.unwrap_or(0), self.commit.is_some() as u32];
+        for (chunk, word) in dest.chunks_exact_mut(4).zip(words) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(SemVer32::SERIALIZED_LEN)
+    }
+    /// Reads a 24-byte serialization from the start of `buf`, rejecting the same
+    /// malformed commit fields as `SemVer::from_bytes_strict`. Trailing bytes are ignored.
+    pub fn read_from(buf: &[u8]) -> Result<SemVer32, Error> {
+        let src = buf.get(..SemVer32::SERIALIZED_LEN).ok_or(Error::Truncated)?;
+        let mut words = [0u32; 6];
+        for (word, chunk) in words.iter_mut().zip(src.chunks_exact(4)) {
+            *word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        }
+        let [maj, min, rev, extra, commit, has_commit] = words;
+        let commit = match (has_commit, commit) {
+            (0, 0) => None,
+            (0, _) => return Err(Error::Malformed("nonzero commit padding")),
+            (1, commit) => Some(commit),
+            _ => return Err(Error::Malformed("invalid commit flag")),
+        };
+        Ok(SemVer32 { maj, min, rev, extra, commit })
+    }
+    /// Narrows to a `SemVer`, clamping each component to `u16::MAX`. The order between
+    /// two wide versions is not preserved once they saturate; use `TryFrom` where that
+    /// matters.
+    pub fn to_semver_saturating(&self) -> SemVer {
+        let clamp = |v: u32| u16::try_from(v).unwrap_or(u16::MAX);
+        SemVer {
+            extra: clamp(self.extra),
+            commit: self.commit,
+            ..SemVer::new(clamp(self.maj), clamp(self.min), clamp(self.rev))
+        }
+    }
+}
+
+impl From<SemVer> for SemVer32 {
+    fn from(ver: SemVer) -> SemVer32 {
+        SemVer32 {
+            maj: ver.maj.into(),
+            min: ver.min.into(),
+            rev: ver.rev.into(),
+            extra: ver.extra.into(),
+            commit: ver.commit,
+        }
+    }
+}
+/// Lossless narrowing; fails with `Error::Overflow` naming the first component that
+/// does not fit in u16.
+impl TryFrom<SemVer32> for SemVer {
+    type Error = Error;
+    fn try_from(ver: SemVer32) -> Result<SemVer, Error> {
+        let narrow = SemVer::try_from((ver.maj, ver.min, ver.rev, ver.extra))?;
+        Ok(SemVer { commit: ver.commit, ..narrow })
+    }
+}
+
+/// Same text form as `SemVer`, including the alternate `{:#}` that leaves out a zero
+/// `extra`.
+impl fmt::Display for SemVer32 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}.{}.{}", self.maj, self.min, self.rev)?;
+        if !(f.alternate() && self.extra == 0) {
+            write!(f, "-{}", self.extra)?;
+        }
+        if let Some(commit) = self.commit_str() {
+            write!(f, "-g{}", commit)?;
+        }
+        Ok(())
+    }
+}
+/// Accepts the same forms as `SemVer::from_str`, with components up to `u32::MAX`.
+impl core::str::FromStr for SemVer32 {
+    type Err = ParseError;
+    fn from_str(revstr: &str) -> Result<Self, ParseError> {
+        let ([maj, min, rev, extra], commit, _) = crate::parse_components::<u32>(revstr)?;
+        Ok(SemVer32 { maj, min, rev, extra, commit })
+    }
+}
+/// As with `SemVer`, the commit takes part in `==` but not in the ordering.
+impl PartialEq for SemVer32 {
+    fn eq(&self, other: &Self) -> bool {
+        (self.maj, self.min, self.rev, self.extra, self.commit)
+            == (other.maj, other.min, other.rev, other.extra, other.commit)
+    }
+}
+impl Ord for SemVer32 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.maj, self.min, self.rev, self.extra).cmp(&(other.maj, other.min, other.rev, other.extra))
+    }
+}
+impl PartialOrd for SemVer32 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+// comparisons with `SemVer` widen it first, which loses nothing
+impl PartialEq<SemVer> for SemVer32 {
+    fn eq(&self, other: &SemVer) -> bool {
+        *self == SemVer32::from(*other)
+    }
+}
+impl PartialEq<SemVer32> for SemVer {
+    fn eq(&self, other: &SemVer32) -> bool {
+        SemVer32::from(*self) == *other
+    }
+}
+impl PartialOrd<SemVer> for SemVer32 {
+    fn partial_cmp(&self, other: &SemVer) -> Option<Ordering> {
+        Some(self.cmp(&SemVer32::from(*other)))
+    }
+}
+impl PartialOrd<SemVer32> for SemVer {
+    fn partial_cmp(&self, other: &SemVer32) -> Option<Ordering> {
+        Some(SemVer32::from(*self).cmp(other))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_semver32() {
+        let wide: SemVer32 = "v0.9.8-70000-gabcd1234".parse().unwrap();
+        assert_eq!(wide.extra, 70000);
+        assert_eq!(wide.to_string(), "v0.9.8-70000-gabcd1234");
+        assert_eq!("v1.2.3".parse::<SemVer32>().map(|v| format!("{:#}", v)), Ok("v1.2.3".to_string()));
+        assert_eq!("v1.2.3-gabc".parse::<SemVer32>(), Ok(SemVer32 { commit: Some(0xabc), ..SemVer32::new(1, 2, 3) }));
+        assert_eq!("v1.2".parse::<SemVer32>(), Err(ParseError::FieldCount));
+        assert_eq!("v1.2.x".parse::<SemVer32>(), Err(ParseError::Component("rev")));
+        let short: SemVer32 = "v1.2.3-0-g0abc123".parse().unwrap();
+        assert_eq!(short.to_string(), "v1.2.3-0-gabc123");
+        assert_eq!(short.commit_str().as_deref(), Some("abc123"));
+        assert_eq!(SemVer::try_from(short).map(|v| v.to_string()), Ok("v1.2.3-0-gabc123".to_string()));
+
+        let mut buf = [0u8; SemVer32::SERIALIZED_LEN];
+        assert_eq!(wide.write_to(&mut buf), Ok(SemVer32::SERIALIZED_LEN));
+        assert_eq!(SemVer32::read_from(&buf), Ok(wide));
+        assert_eq!(SemVer32::read_from(&buf[..23]), Err(Error::Truncated));
+        assert_eq!(wide.write_to(&mut buf[..23]), Err(Error::BufferTooSmall { needed: 24 }));
+        buf[20] = 2;
+        assert_eq!(SemVer32::read_from(&buf), Err(Error::Malformed("invalid commit flag")));
+
+        assert!(wide > SemVer::from_str("v0.9.8-65535").unwrap());
+        assert!(SemVer::from_str("v0.9.9").unwrap() > wide);
+        assert!(SemVer32::new(1, 2, 3) == SemVer::new(1, 2, 3));
+        assert!(SemVer::from_str("v1.2.3-0-gabc").unwrap() != SemVer32::new(1, 2, 3));
+    }
+    #[test]
+    fn test_semver32_conversions() {
+        let ver = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        assert_eq!(SemVer::try_from(SemVer32::from(ver)), Ok(ver));
+        let wide: SemVer32 = "v0.9.8-70000-gabcd1234".parse().unwrap();
+        assert_eq!(SemVer::try_from(wide), Err(Error::Overflow("extra")));
+        assert_eq!(wide.to_semver_saturating(), SemVer::from_str("v0.9.8-65535-gabcd1234").unwrap());
+    }
+}
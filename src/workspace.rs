@@ -0,0 +1,122 @@
+//! Versions of every member of a cargo workspace, from `cargo metadata`, so release
+//! tooling can confirm they were all bumped together before tagging.
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::Path;
+use std::process::Command;
+use crate::{ParseError, SemVer};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceError {
+    /// `cargo` could not be spawned.
+    Exec(io::ErrorKind),
+    /// `cargo metadata` exited unsuccessfully; `status` is `None` if it was killed.
+    Failed { status: Option<i32>, stderr: String },
+    /// The output was not the JSON `cargo metadata --format-version 1` produces.
+    Metadata(String),
+    /// A member's version can't be represented, e.g. a pre-release.
+    Version { package: String, reason: ParseError },
+}
+impl fmt::Display for WorkspaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkspaceError::Exec(kind) => write!(f, "failed to execute cargo: {}", kind),
+            WorkspaceError::Failed { status: Some(code), stderr } => write!(f, "cargo metadata exited with status {}: {}", code, stderr.trim()),
+            WorkspaceError::Failed { status: None, stderr } => write!(f, "cargo metadata was terminated: {}", stderr.trim()),
+            WorkspaceError::Metadata(e) => write!(f, "unexpected cargo metadata output: {}", e),
+            WorkspaceError::Version { package, reason } => write!(f, "version of {}: {}", package, reason),
+        }
+    }
+}
+impl std::error::Error for WorkspaceError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            WorkspaceError::Version { reason, .. } => Some(reason),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the version of each member of the workspace containing `dir` (or the current
+/// directory), keyed by package name.
+pub fn workspace_versions(dir: Option<&Path>) -> Result<BTreeMap<String, SemVer>, WorkspaceError> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let mut cmd = Command::new(cargo);
+    cmd.args(["metadata", "--format-version", "1", "--no-deps"]);
+    if let Some(dir) = dir {
+        cmd.current_dir(dir);
+    }
+    let output = cmd.output().map_err(|e| WorkspaceError::Exec(e.kind()))?;
+    if !output.status.success() {
+        return Err(WorkspaceError::Failed {
+            status: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        });
+    }
+    parse_metadata(&output.stdout)
+}
+
+/// The version shared by every member, or `None` if they disagree (or there are none).
+pub fn common_version(versions: &BTreeMap<String, SemVer>) -> Option<SemVer> {
+    let mut iter = versions.values();
+    let first = *iter.next()?;
+    iter.all(|v| *v == first).then_some(first)
+}
+
+fn parse_metadata(json: &[u8]) -> Result<BTreeMap<String, SemVer>, WorkspaceError> {
+    let bad = |what: &str| WorkspaceError::Metadata(what.to_string());
+    let metadata: serde_json::Value = serde_json::from_slice(json).map_err(|e| WorkspaceError::Metadata(e.to_string()))?;
+    let members = metadata["workspace_members"].as_array().ok_or_else(|| bad("no workspace_members"))?;
+    let packages = metadata["packages"].as_array().ok_or_else(|| bad("no packages"))?;
+    let mut versions = BTreeMap::new();
+    for package in packages.iter().filter(|p| members.contains(&p["id"])) {
+        let name = package["name"].as_str().ok_or_else(|| bad("package without a name"))?;
+        let version = package["version"].as_str().ok_or_else(|| bad("package without a version"))?;
+        let version = SemVer::from_cargo_version(version)
+            .map_err(|reason| WorkspaceError::Version { package: name.to_string(), reason })?;
+        versions.insert(name.to_string(), version);
+    }
+    Ok(versions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_workspace_versions() {
+        let versions = workspace_versions(Some(Path::new(env!("CARGO_MANIFEST_DIR")))).unwrap();
+        let ours = SemVer::from_cargo_version(env!("CARGO_PKG_VERSION")).unwrap();
+        assert_eq!(versions.get("xous-semver"), Some(&ours));
+        assert!(versions.contains_key("xous-semver-macros"));
+    }
+    #[test]
+    fn test_parse_metadata() {
+        let json = br#"{
+            "packages": [
+                {"name": "loader", "version": "0.9.8", "id": "loader 0.9.8"},
+                {"name": "kernel", "version": "0.9.7", "id": "kernel 0.9.7"},
+                {"name": "vendored", "version": "1.0.0", "id": "vendored 1.0.0"}
+            ],
+            "workspace_members": ["loader 0.9.8", "kernel 0.9.7"]
+        }"#;
+        let versions = parse_metadata(json).unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions["loader"], SemVer::new(0, 9, 8));
+        assert_eq!(common_version(&versions), None);
+        let mut lockstep = versions.clone();
+        lockstep.insert("kernel".to_string(), SemVer::new(0, 9, 8));
+        assert_eq!(common_version(&lockstep), Some(SemVer::new(0, 9, 8)));
+        assert_eq!(common_version(&BTreeMap::new()), None);
+
+        let json = br#"{"packages": [{"name": "rc", "version": "1.0.0-rc.1", "id": "rc"}], "workspace_members": ["rc"]}"#;
+        assert_eq!(
+            parse_metadata(json),
+            Err(WorkspaceError::Version { package: "rc".to_string(), reason: ParseError::FieldCount })
+        );
+        assert!(matches!(parse_metadata(b"{}"), Err(WorkspaceError::Metadata(_))));
+        assert!(matches!(parse_metadata(b"{\"packages\": [}"), Err(WorkspaceError::Metadata(_))));
+        let junk = br#"{"packages": [1-2e], "workspace_members": ["\ud800"]}"#;
+        assert!(matches!(parse_metadata(junk), Err(WorkspaceError::Metadata(_))));
+    }
+}
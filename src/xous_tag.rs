@@ -0,0 +1,116 @@
+//! Version tags in the Xous image argument stream, so the loader can report the
+//! versions of the kernel and initial programs it booted.
+//!
+//! Each tag in the stream is a sequence of little-endian 32-bit words: the tag name
+//! (four ASCII bytes), then a word whose low half is a CRC-16 and whose high half is
+//! the payload size in words, then the payload. A version tag's payload is the 16-byte
+//! serialization, four words. The CRC is CRC-16/X-25 over the payload, as the Xous
+//! image tools compute it.
+use crate::crc::crc16;
+use crate::{Error, SemVer};
+
+/// Tag name for the kernel version.
+pub const XOUS_TAG_KERNEL: u32 = u32::from_le_bytes(*b"KVer");
+/// Tag name for an initial program's version.
+pub const XOUS_TAG_PROGRAM: u32 = u32::from_le_bytes(*b"PVer");
+/// Length of a version tag, header included.
+pub const XOUS_TAG_LEN: usize = 8 + SemVer::SERIALIZED_LEN;
+
+impl SemVer {
+    /// Writes a version tag named `tag` to the start of `buf`, returning the number of
+    /// bytes written.
+    pub fn write_xous_tag(&self, tag: u32, buf: &mut [u8]) -> Result<usize, Error> {
+        let dest = buf.get_mut(..XOUS_TAG_LEN).ok_or(Error::BufferTooSmall { needed: XOUS_TAG_LEN })?;
+        let words = (SemVer::SERIALIZED_LEN / 4) as u32;
+        let ser = <[u8; 16]>::from(self);
+        dest[..4].copy_from_slice(&tag.to_le_bytes());
+        dest[4..8].copy_from_slice(&(words << 16 | crc16(&ser) as u32).to_le_bytes());
+        dest[8..].copy_from_slice(&ser);
+        Ok(XOUS_TAG_LEN)
+    }
+}
+
+/// Walks a tag stream and returns the version in every tag named `tag`, in order. Other
+/// tags are skipped without checking their CRC. Fails if the stream ends in the middle
+/// of a tag, if a matching tag isn't a valid version, or with `Error::Corrupt` if its
+/// CRC doesn't match.
+pub fn xous_tag_versions(stream: &[u8], tag: u32) -> XousTagVersions<'_> {
+    XousTagVersions { stream, tag }
+}
+
+pub struct XousTagVersions<'a> {
+    stream: &'a [u8],
+    tag: u32,
+}
+
+impl Iterator for XousTagVersions<'_> {
+    type Item = Result<SemVer, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.stream.is_empty() {
+            let Some((&[n0, n1, n2, n3, c0, c1, s0, s1], rest)) = self.stream.split_first_chunk::<8>() else {
+                self.stream = &[];
+                return Some(Err(Error::Truncated));
+            };
+            let name = u32::from_le_bytes([n0, n1, n2, n3]);
+            let size = u16::from_le_bytes([s0, s1]) as usize * 4;
+            let Some((payload, rest)) = rest.split_at_checked(size) else {
+                self.stream = &[];
+                return Some(Err(Error::Truncated));
+            };
+            self.stream = rest;
+            if name != self.tag {
+                continue;
+            }
+            let Ok(bytes) = <&[u8; 16]>::try_from(payload) else {
+                return Some(Err(Error::Malformed("version tag has the wrong size")));
+            };
+            if crc16(bytes) != u16::from_le_bytes([c0, c1]) {
+                return Some(Err(Error::Corrupt));
+            }
+            return Some(SemVer::from_bytes_strict(bytes));
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[test]
+    fn test_xous_tags() {
+        let kernel = SemVer::from_str("v0.9.8-760-gabcd1234").unwrap();
+        let shell = SemVer::new(0, 9, 7);
+        let mut stream = [0u8; 8 + 8 + 3 * XOUS_TAG_LEN];
+        // an unrelated two-word tag first
+        stream[..4].copy_from_slice(b"XArg");
+        stream[4..8].copy_from_slice(&(2u32 << 16).to_le_bytes());
+        let mut at = 16;
+        at += kernel.write_xous_tag(XOUS_TAG_KERNEL, &mut stream[at..]).unwrap();
+        at += shell.write_xous_tag(XOUS_TAG_PROGRAM, &mut stream[at..]).unwrap();
+        at += SemVer::new(1, 0, 0).write_xous_tag(XOUS_TAG_PROGRAM, &mut stream[at..]).unwrap();
+        assert_eq!(at, stream.len());
+        assert_eq!(stream[16..20], *b"KVer");
+        assert_eq!(stream[20..24], [0x6e, 0xb1, 4, 0]);
+
+        let kernels: Vec<_> = xous_tag_versions(&stream, XOUS_TAG_KERNEL).collect();
+        assert_eq!(kernels, vec![Ok(kernel)]);
+        let programs: Vec<_> = xous_tag_versions(&stream, XOUS_TAG_PROGRAM).collect();
+        assert_eq!(programs, vec![Ok(shell), Ok(SemVer::new(1, 0, 0))]);
+
+        let mut corrupt = stream;
+        corrupt[30] ^= 1;
+        assert_eq!(xous_tag_versions(&corrupt, XOUS_TAG_KERNEL).next(), Some(Err(Error::Corrupt)));
+        // the unrelated tag's zero CRC isn't checked
+        assert_eq!(xous_tag_versions(&corrupt, XOUS_TAG_PROGRAM).count(), 2);
+
+        let cut = &stream[..stream.len() - 1];
+        assert_eq!(xous_tag_versions(cut, XOUS_TAG_PROGRAM).last(), Some(Err(Error::Truncated)));
+        stream[4..8].copy_from_slice(&(1u32 << 16).to_le_bytes());
+        stream[..4].copy_from_slice(b"KVer");
+        assert_eq!(
+            xous_tag_versions(&stream[..12], XOUS_TAG_KERNEL).next(),
+            Some(Err(Error::Malformed("version tag has the wrong size")))
+        );
+        assert_eq!(kernel.write_xous_tag(XOUS_TAG_KERNEL, &mut stream[..23]), Err(Error::BufferTooSmall { needed: 24 }));
+    }
+}